@@ -26,6 +26,7 @@ fn fail_on_empty_directory(name: &str) {
 fn build_rocksdb() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=rocksdb/");
+    println!("cargo:rerun-if-changed=c_ext.cc");
 
     let mut config = gcc::Config::new();
     config.include("rocksdb/include/");
@@ -102,6 +103,8 @@ fn build_rocksdb() {
     }
 
     config.file("build_version.cc");
+    // The C bindings that are missing from `db/c.cc`
+    config.file("c_ext.cc");
 
     config.cpp(true);
     config.compile("librocksdb.a");
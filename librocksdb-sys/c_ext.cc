@@ -0,0 +1,1095 @@
+// C bindings for RocksDB features the bundled `db/c.cc` doesn't expose. Unlike `db/c.cc` they
+// return errors as `rocksdb_error_t`, which keeps the code and subcode of the status next to
+// its message. The functions that `db/c.cc` already has are repeated with an `ext` prefix for
+// that reason. Returned values are malloc'ed copies, as in `db/c.cc`.
+
+#include "rocksdb/c.h"
+
+#include <assert.h>
+#include <stdlib.h>
+#include <string.h>
+#include <memory>
+#include <string>
+#include <vector>
+
+#include "rocksdb/comparator.h"
+#include "rocksdb/convenience.h"
+#include "rocksdb/db.h"
+#include "rocksdb/env.h"
+#include "rocksdb/metadata.h"
+#include "rocksdb/options.h"
+#include "rocksdb/statistics.h"
+#include "rocksdb/status.h"
+#include "rocksdb/write_batch.h"
+#include "rocksdb/utilities/backupable_db.h"
+#include "rocksdb/utilities/checkpoint.h"
+#include "rocksdb/utilities/optimistic_transaction_db.h"
+#include "rocksdb/utilities/transaction.h"
+#include "rocksdb/utilities/transaction_db.h"
+#include "rocksdb/utilities/write_batch_with_index.h"
+
+using rocksdb::BackupableDBOptions;
+using rocksdb::BackupEngine;
+using rocksdb::BackupInfo;
+using rocksdb::Checkpoint;
+using rocksdb::ColumnFamilyDescriptor;
+using rocksdb::ColumnFamilyHandle;
+using rocksdb::ColumnFamilyOptions;
+using rocksdb::Comparator;
+using rocksdb::DB;
+using rocksdb::DBOptions;
+using rocksdb::Env;
+using rocksdb::FlushOptions;
+using rocksdb::Iterator;
+using rocksdb::LiveFileMetaData;
+using rocksdb::OptimisticTransactionDB;
+using rocksdb::OptimisticTransactionOptions;
+using rocksdb::Options;
+using rocksdb::ReadOptions;
+using rocksdb::RestoreOptions;
+using rocksdb::Slice;
+using rocksdb::Snapshot;
+using rocksdb::Statistics;
+using rocksdb::Status;
+using rocksdb::Transaction;
+using rocksdb::TransactionDB;
+using rocksdb::TransactionDBOptions;
+using rocksdb::TransactionOptions;
+using rocksdb::WriteBatch;
+using rocksdb::WriteBatchWithIndex;
+using rocksdb::WriteOptions;
+
+extern "C" {
+
+// The same definitions as in `db/c.cc`
+struct rocksdb_t { DB* rep; };
+struct rocksdb_options_t { Options rep; };
+struct rocksdb_writeoptions_t { WriteOptions rep; };
+struct rocksdb_writebatch_t { WriteBatch rep; };
+struct rocksdb_flushoptions_t { FlushOptions rep; };
+struct rocksdb_snapshot_t { const Snapshot* rep; };
+struct rocksdb_column_family_handle_t { ColumnFamilyHandle* rep; };
+struct rocksdb_livefiles_t { std::vector<LiveFileMetaData> rep; };
+struct rocksdb_backup_engine_t { BackupEngine* rep; };
+struct rocksdb_iterator_t { Iterator* rep; };
+struct rocksdb_backup_engine_info_t { std::vector<BackupInfo> rep; };
+struct rocksdb_restore_options_t { RestoreOptions rep; };
+
+struct rocksdb_statistics_t { std::shared_ptr<Statistics> rep; };
+struct rocksdb_transactiondb_options_t { TransactionDBOptions rep; };
+struct rocksdb_transactiondb_t { TransactionDB* rep; };
+struct rocksdb_transaction_options_t { TransactionOptions rep; };
+struct rocksdb_transaction_t { Transaction* rep; };
+struct rocksdb_optimistictransactiondb_t { OptimisticTransactionDB* rep; };
+struct rocksdb_optimistictransaction_options_t { OptimisticTransactionOptions rep; };
+struct rocksdb_checkpoint_t { Checkpoint* rep; };
+struct rocksdb_backupable_db_options_t { BackupableDBOptions rep; };
+// `comparator` orders the keys the batch writes without a column family handle.
+struct rocksdb_writebatch_wi_t {
+  WriteBatchWithIndex* rep;
+  const Comparator* comparator;
+};
+
+struct rocksdb_error_t {
+  Status rep;
+  std::string message;
+};
+
+// `rocksdb_readoptions_t` has more members in `db/c.cc`, only the leading `ReadOptions` is
+// used here.
+static const ReadOptions& GetReadOptions(const rocksdb_readoptions_t* options) {
+  return *reinterpret_cast<const ReadOptions*>(options);
+}
+
+static bool SaveError(rocksdb_error_t** errptr, const Status& s) {
+  assert(errptr != nullptr);
+  if (s.ok()) {
+    return false;
+  } else if (*errptr != nullptr) {
+    delete *errptr;
+  }
+  rocksdb_error_t* err = new rocksdb_error_t;
+  err->rep = s;
+  err->message = s.ToString();
+  *errptr = err;
+  return true;
+}
+
+static char* CopyString(const std::string& str) {
+  char* result = reinterpret_cast<char*>(malloc(sizeof(char) * str.size()));
+  memcpy(result, str.data(), sizeof(char) * str.size());
+  return result;
+}
+
+// Returns a copy of the value of a successful get, a missing key isn't an error.
+static char* GetResult(const Status& s, const std::string& value, size_t* vallen,
+                       rocksdb_error_t** errptr) {
+  if (s.ok()) {
+    *vallen = value.size();
+    return CopyString(value);
+  }
+  *vallen = 0;
+  if (!s.IsNotFound()) {
+    SaveError(errptr, s);
+  }
+  return nullptr;
+}
+
+static std::vector<ColumnFamilyDescriptor> GetColumnFamilies(
+    int num_column_families, const char** column_family_names,
+    const rocksdb_options_t** column_family_options) {
+  std::vector<ColumnFamilyDescriptor> column_families;
+  for (int i = 0; i < num_column_families; i++) {
+    column_families.push_back(ColumnFamilyDescriptor(
+        std::string(column_family_names[i]),
+        ColumnFamilyOptions(column_family_options[i]->rep)));
+  }
+  return column_families;
+}
+
+static void SaveHandles(const std::vector<ColumnFamilyHandle*>& handles,
+                        rocksdb_column_family_handle_t** column_family_handles) {
+  for (size_t i = 0; i < handles.size(); i++) {
+    rocksdb_column_family_handle_t* c_handle = new rocksdb_column_family_handle_t;
+    c_handle->rep = handles[i];
+    column_family_handles[i] = c_handle;
+  }
+}
+
+static Status DeleteFilesInRange(rocksdb_t* db, ColumnFamilyHandle* column_family,
+                                 const char* start_key, size_t start_key_len,
+                                 const char* limit_key, size_t limit_key_len) {
+  Slice start(start_key, start_key_len);
+  Slice limit(limit_key, limit_key_len);
+  return rocksdb::DeleteFilesInRange(db->rep, column_family, &start, &limit);
+}
+
+// Errors
+
+// The values of `Status::Code` in `rocksdb/status.h`.
+int rocksdb_error_code(const rocksdb_error_t* err) {
+  return static_cast<int>(err->rep.code());
+}
+
+// The values of `Status::SubCode` in `rocksdb/status.h`.
+int rocksdb_error_subcode(const rocksdb_error_t* err) {
+  return static_cast<int>(err->rep.subcode());
+}
+
+// The returned message is owned by `err`.
+const char* rocksdb_error_message(const rocksdb_error_t* err) {
+  return err->message.c_str();
+}
+
+void rocksdb_error_destroy(rocksdb_error_t* err) {
+  delete err;
+}
+
+// Databases
+
+rocksdb_t* rocksdb_ext_open_column_families(
+    const rocksdb_options_t* db_options, const char* name, int num_column_families,
+    const char** column_family_names, const rocksdb_options_t** column_family_options,
+    rocksdb_column_family_handle_t** column_family_handles, rocksdb_error_t** errptr) {
+  DB* db;
+  std::vector<ColumnFamilyHandle*> handles;
+  if (SaveError(errptr, DB::Open(DBOptions(db_options->rep), std::string(name),
+                                 GetColumnFamilies(num_column_families, column_family_names,
+                                                   column_family_options),
+                                 &handles, &db))) {
+    return nullptr;
+  }
+  SaveHandles(handles, column_family_handles);
+  rocksdb_t* result = new rocksdb_t;
+  result->rep = db;
+  return result;
+}
+
+rocksdb_t* rocksdb_ext_open_for_read_only_column_families(
+    const rocksdb_options_t* db_options, const char* name, int num_column_families,
+    const char** column_family_names, const rocksdb_options_t** column_family_options,
+    rocksdb_column_family_handle_t** column_family_handles,
+    unsigned char error_if_log_file_exist, rocksdb_error_t** errptr) {
+  DB* db;
+  std::vector<ColumnFamilyHandle*> handles;
+  if (SaveError(errptr, DB::OpenForReadOnly(DBOptions(db_options->rep), std::string(name),
+                                            GetColumnFamilies(num_column_families,
+                                                              column_family_names,
+                                                              column_family_options),
+                                            &handles, &db, error_if_log_file_exist))) {
+    return nullptr;
+  }
+  SaveHandles(handles, column_family_handles);
+  rocksdb_t* result = new rocksdb_t;
+  result->rep = db;
+  return result;
+}
+
+// The returned list must be released with `rocksdb_list_column_families_destroy`.
+char** rocksdb_ext_list_column_families(const rocksdb_options_t* options, const char* name,
+                                        size_t* lencf, rocksdb_error_t** errptr) {
+  std::vector<std::string> families;
+  if (SaveError(errptr, DB::ListColumnFamilies(DBOptions(options->rep), std::string(name),
+                                               &families))) {
+    *lencf = 0;
+    return nullptr;
+  }
+  *lencf = families.size();
+  char** result = static_cast<char**>(malloc(sizeof(char*) * families.size()));
+  for (size_t i = 0; i < families.size(); i++) {
+    result[i] = strdup(families[i].c_str());
+  }
+  return result;
+}
+
+rocksdb_column_family_handle_t* rocksdb_ext_create_column_family(
+    rocksdb_t* db, const rocksdb_options_t* column_family_options,
+    const char* column_family_name, rocksdb_error_t** errptr) {
+  ColumnFamilyHandle* handle;
+  if (SaveError(errptr, db->rep->CreateColumnFamily(
+                            ColumnFamilyOptions(column_family_options->rep),
+                            std::string(column_family_name), &handle))) {
+    return nullptr;
+  }
+  rocksdb_column_family_handle_t* result = new rocksdb_column_family_handle_t;
+  result->rep = handle;
+  return result;
+}
+
+void rocksdb_ext_drop_column_family(rocksdb_t* db, rocksdb_column_family_handle_t* handle,
+                                    rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->DropColumnFamily(handle->rep));
+}
+
+void rocksdb_ext_put(rocksdb_t* db, const rocksdb_writeoptions_t* options, const char* key,
+                     size_t keylen, const char* val, size_t vallen, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Put(options->rep, Slice(key, keylen), Slice(val, vallen)));
+}
+
+void rocksdb_ext_put_cf(rocksdb_t* db, const rocksdb_writeoptions_t* options,
+                        rocksdb_column_family_handle_t* column_family, const char* key,
+                        size_t keylen, const char* val, size_t vallen,
+                        rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Put(options->rep, column_family->rep, Slice(key, keylen),
+                                 Slice(val, vallen)));
+}
+
+void rocksdb_ext_delete(rocksdb_t* db, const rocksdb_writeoptions_t* options, const char* key,
+                        size_t keylen, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Delete(options->rep, Slice(key, keylen)));
+}
+
+void rocksdb_ext_delete_cf(rocksdb_t* db, const rocksdb_writeoptions_t* options,
+                           rocksdb_column_family_handle_t* column_family, const char* key,
+                           size_t keylen, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Delete(options->rep, column_family->rep, Slice(key, keylen)));
+}
+
+void rocksdb_singledelete(rocksdb_t* db, const rocksdb_writeoptions_t* options, const char* key,
+                          size_t keylen, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->SingleDelete(options->rep, Slice(key, keylen)));
+}
+
+void rocksdb_singledelete_cf(rocksdb_t* db, const rocksdb_writeoptions_t* options,
+                             rocksdb_column_family_handle_t* column_family, const char* key,
+                             size_t keylen, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->SingleDelete(options->rep, column_family->rep, Slice(key, keylen)));
+}
+
+void rocksdb_delete_range_cf(rocksdb_t* db, const rocksdb_writeoptions_t* options,
+                             rocksdb_column_family_handle_t* column_family,
+                             const char* start_key, size_t start_key_len, const char* end_key,
+                             size_t end_key_len, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->DeleteRange(options->rep, column_family->rep,
+                                         Slice(start_key, start_key_len),
+                                         Slice(end_key, end_key_len)));
+}
+
+void rocksdb_ext_merge(rocksdb_t* db, const rocksdb_writeoptions_t* options, const char* key,
+                       size_t keylen, const char* val, size_t vallen,
+                       rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Merge(options->rep, Slice(key, keylen), Slice(val, vallen)));
+}
+
+void rocksdb_ext_merge_cf(rocksdb_t* db, const rocksdb_writeoptions_t* options,
+                          rocksdb_column_family_handle_t* column_family, const char* key,
+                          size_t keylen, const char* val, size_t vallen,
+                          rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Merge(options->rep, column_family->rep, Slice(key, keylen),
+                                   Slice(val, vallen)));
+}
+
+void rocksdb_ext_write(rocksdb_t* db, const rocksdb_writeoptions_t* options,
+                       rocksdb_writebatch_t* batch, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Write(options->rep, &batch->rep));
+}
+
+char* rocksdb_ext_get(rocksdb_t* db, const rocksdb_readoptions_t* options, const char* key,
+                      size_t keylen, size_t* vallen, rocksdb_error_t** errptr) {
+  std::string value;
+  Status s = db->rep->Get(GetReadOptions(options), Slice(key, keylen), &value);
+  return GetResult(s, value, vallen, errptr);
+}
+
+char* rocksdb_ext_get_cf(rocksdb_t* db, const rocksdb_readoptions_t* options,
+                         rocksdb_column_family_handle_t* column_family, const char* key,
+                         size_t keylen, size_t* vallen, rocksdb_error_t** errptr) {
+  std::string value;
+  Status s = db->rep->Get(GetReadOptions(options), column_family->rep, Slice(key, keylen),
+                          &value);
+  return GetResult(s, value, vallen, errptr);
+}
+
+// Fills in one value or error per key, a missing key leaves both null.
+static void SaveMultiGetResults(const std::vector<Status>& statuses,
+                                const std::vector<std::string>& values, char** values_list,
+                                size_t* values_list_sizes, rocksdb_error_t** errs) {
+  for (size_t i = 0; i < statuses.size(); i++) {
+    values_list[i] = nullptr;
+    values_list_sizes[i] = 0;
+    errs[i] = nullptr;
+    if (statuses[i].ok()) {
+      values_list[i] = CopyString(values[i]);
+      values_list_sizes[i] = values[i].size();
+    } else if (!statuses[i].IsNotFound()) {
+      SaveError(&errs[i], statuses[i]);
+    }
+  }
+}
+
+void rocksdb_ext_multi_get(rocksdb_t* db, const rocksdb_readoptions_t* options,
+                           size_t num_keys, const char* const* keys_list,
+                           const size_t* keys_list_sizes, char** values_list,
+                           size_t* values_list_sizes, rocksdb_error_t** errs) {
+  std::vector<Slice> keys(num_keys);
+  for (size_t i = 0; i < num_keys; i++) {
+    keys[i] = Slice(keys_list[i], keys_list_sizes[i]);
+  }
+  std::vector<std::string> values(num_keys);
+  std::vector<Status> statuses = db->rep->MultiGet(GetReadOptions(options), keys, &values);
+  SaveMultiGetResults(statuses, values, values_list, values_list_sizes, errs);
+}
+
+void rocksdb_ext_multi_get_cf(rocksdb_t* db, const rocksdb_readoptions_t* options,
+                              const rocksdb_column_family_handle_t* const* column_families,
+                              size_t num_keys, const char* const* keys_list,
+                              const size_t* keys_list_sizes, char** values_list,
+                              size_t* values_list_sizes, rocksdb_error_t** errs) {
+  std::vector<ColumnFamilyHandle*> cfs(num_keys);
+  std::vector<Slice> keys(num_keys);
+  for (size_t i = 0; i < num_keys; i++) {
+    cfs[i] = column_families[i]->rep;
+    keys[i] = Slice(keys_list[i], keys_list_sizes[i]);
+  }
+  std::vector<std::string> values(num_keys);
+  std::vector<Status> statuses =
+      db->rep->MultiGet(GetReadOptions(options), cfs, keys, &values);
+  SaveMultiGetResults(statuses, values, values_list, values_list_sizes, errs);
+}
+
+void rocksdb_ext_flush(rocksdb_t* db, const rocksdb_flushoptions_t* options,
+                       rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Flush(options->rep));
+}
+
+void rocksdb_flush_cf(rocksdb_t* db, const rocksdb_flushoptions_t* options,
+                      rocksdb_column_family_handle_t* column_family, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Flush(options->rep, column_family->rep));
+}
+
+void rocksdb_ext_delete_file_in_range(rocksdb_t* db, const char* start_key,
+                                      size_t start_key_len, const char* limit_key,
+                                      size_t limit_key_len, rocksdb_error_t** errptr) {
+  SaveError(errptr, DeleteFilesInRange(db, db->rep->DefaultColumnFamily(), start_key,
+                                       start_key_len, limit_key, limit_key_len));
+}
+
+void rocksdb_ext_delete_file_in_range_cf(rocksdb_t* db,
+                                         rocksdb_column_family_handle_t* column_family,
+                                         const char* start_key, size_t start_key_len,
+                                         const char* limit_key, size_t limit_key_len,
+                                         rocksdb_error_t** errptr) {
+  SaveError(errptr, DeleteFilesInRange(db, column_family->rep, start_key, start_key_len,
+                                       limit_key, limit_key_len));
+}
+
+// The returned name is owned by `files`.
+const char* rocksdb_livefiles_column_family_name(const rocksdb_livefiles_t* files, int index) {
+  return files->rep[index].column_family_name.c_str();
+}
+
+void rocksdb_ext_destroy_db(const rocksdb_options_t* options, const char* name,
+                            rocksdb_error_t** errptr) {
+  SaveError(errptr, rocksdb::DestroyDB(name, options->rep));
+}
+
+void rocksdb_ext_repair_db(const rocksdb_options_t* options, const char* name,
+                           rocksdb_error_t** errptr) {
+  SaveError(errptr, rocksdb::RepairDB(name, options->rep));
+}
+
+// Write batches
+
+void rocksdb_writebatch_singledelete(rocksdb_writebatch_t* b, const char* key, size_t klen) {
+  b->rep.SingleDelete(Slice(key, klen));
+}
+
+void rocksdb_writebatch_singledelete_cf(rocksdb_writebatch_t* b,
+                                        rocksdb_column_family_handle_t* column_family,
+                                        const char* key, size_t klen) {
+  b->rep.SingleDelete(column_family->rep, Slice(key, klen));
+}
+
+void rocksdb_writebatch_delete_range(rocksdb_writebatch_t* b, const char* start_key,
+                                     size_t start_key_len, const char* end_key,
+                                     size_t end_key_len) {
+  b->rep.DeleteRange(Slice(start_key, start_key_len), Slice(end_key, end_key_len));
+}
+
+void rocksdb_writebatch_delete_range_cf(rocksdb_writebatch_t* b,
+                                        rocksdb_column_family_handle_t* column_family,
+                                        const char* start_key, size_t start_key_len,
+                                        const char* end_key, size_t end_key_len) {
+  b->rep.DeleteRange(column_family->rep, Slice(start_key, start_key_len),
+                     Slice(end_key, end_key_len));
+}
+
+// Backups
+
+void rocksdb_ext_backup_engine_create_new_backup(rocksdb_backup_engine_t* be, rocksdb_t* db,
+                                                 rocksdb_error_t** errptr) {
+  SaveError(errptr, be->rep->CreateNewBackup(db->rep));
+}
+
+void rocksdb_ext_backup_engine_purge_old_backups(rocksdb_backup_engine_t* be,
+                                                 uint32_t num_backups_to_keep,
+                                                 rocksdb_error_t** errptr) {
+  SaveError(errptr, be->rep->PurgeOldBackups(num_backups_to_keep));
+}
+
+void rocksdb_ext_backup_engine_restore_db_from_latest_backup(
+    rocksdb_backup_engine_t* be, const char* db_dir, const char* wal_dir,
+    const rocksdb_restore_options_t* restore_options, rocksdb_error_t** errptr) {
+  SaveError(errptr, be->rep->RestoreDBFromLatestBackup(std::string(db_dir),
+                                                       std::string(wal_dir),
+                                                       restore_options->rep));
+}
+
+void rocksdb_backup_engine_restore_db_from_backup(
+    rocksdb_backup_engine_t* be, const char* db_dir, const char* wal_dir,
+    const rocksdb_restore_options_t* restore_options, uint32_t backup_id,
+    rocksdb_error_t** errptr) {
+  SaveError(errptr, be->rep->RestoreDBFromBackup(backup_id, std::string(db_dir),
+                                                 std::string(wal_dir),
+                                                 restore_options->rep));
+}
+
+void rocksdb_backup_engine_verify_backup(rocksdb_backup_engine_t* be, uint32_t backup_id,
+                                         rocksdb_error_t** errptr) {
+  SaveError(errptr, be->rep->VerifyBackup(backup_id));
+}
+
+void rocksdb_backup_engine_delete_backup(rocksdb_backup_engine_t* be, uint32_t backup_id,
+                                         rocksdb_error_t** errptr) {
+  SaveError(errptr, be->rep->DeleteBackup(backup_id));
+}
+
+rocksdb_backupable_db_options_t* rocksdb_backupable_db_options_create(const char* backup_dir) {
+  return new rocksdb_backupable_db_options_t{BackupableDBOptions(std::string(backup_dir))};
+}
+
+void rocksdb_backupable_db_options_set_share_table_files(
+    rocksdb_backupable_db_options_t* options, unsigned char val) {
+  options->rep.share_table_files = val;
+}
+
+void rocksdb_backupable_db_options_set_sync(rocksdb_backupable_db_options_t* options,
+                                            unsigned char val) {
+  options->rep.sync = val;
+}
+
+void rocksdb_backupable_db_options_set_destroy_old_data(
+    rocksdb_backupable_db_options_t* options, unsigned char val) {
+  options->rep.destroy_old_data = val;
+}
+
+void rocksdb_backupable_db_options_set_backup_log_files(
+    rocksdb_backupable_db_options_t* options, unsigned char val) {
+  options->rep.backup_log_files = val;
+}
+
+void rocksdb_backupable_db_options_set_backup_rate_limit(
+    rocksdb_backupable_db_options_t* options, uint64_t limit) {
+  options->rep.backup_rate_limit = limit;
+}
+
+void rocksdb_backupable_db_options_set_restore_rate_limit(
+    rocksdb_backupable_db_options_t* options, uint64_t limit) {
+  options->rep.restore_rate_limit = limit;
+}
+
+void rocksdb_backupable_db_options_destroy(rocksdb_backupable_db_options_t* options) {
+  delete options;
+}
+
+// The options are copied, `backup_dir` overrides the directory they were created with.
+rocksdb_backup_engine_t* rocksdb_backup_engine_open_opts(
+    const rocksdb_backupable_db_options_t* options, const char* backup_dir,
+    rocksdb_error_t** errptr) {
+  BackupableDBOptions opts = options->rep;
+  opts.backup_dir = std::string(backup_dir);
+  BackupEngine* be;
+  if (SaveError(errptr, BackupEngine::Open(Env::Default(), opts, &be))) {
+    return nullptr;
+  }
+  rocksdb_backup_engine_t* result = new rocksdb_backup_engine_t;
+  result->rep = be;
+  return result;
+}
+
+void rocksdb_backup_engine_create_new_backup_with_metadata(
+    rocksdb_backup_engine_t* be, rocksdb_t* db, const char* app_metadata,
+    size_t app_metadata_len, unsigned char flush_before_backup, rocksdb_error_t** errptr) {
+  SaveError(errptr, be->rep->CreateNewBackupWithMetadata(
+                        db->rep, std::string(app_metadata, app_metadata_len),
+                        flush_before_backup));
+}
+
+// The returned metadata is owned by `info` and not null terminated.
+const char* rocksdb_backup_engine_info_app_metadata(const rocksdb_backup_engine_info_t* info,
+                                                    int index, size_t* len) {
+  const std::string& app_metadata = info->rep[index].app_metadata;
+  *len = app_metadata.size();
+  return app_metadata.data();
+}
+
+// Statistics
+
+// Returns null if the options have no statistics. The returned statistics are shared with the
+// options and with every database opened with them.
+rocksdb_statistics_t* rocksdb_options_get_statistics(const rocksdb_options_t* opt) {
+  if (!opt->rep.statistics) {
+    return nullptr;
+  }
+  rocksdb_statistics_t* result = new rocksdb_statistics_t;
+  result->rep = opt->rep.statistics;
+  return result;
+}
+
+char* rocksdb_statistics_to_string(const rocksdb_statistics_t* statistics) {
+  return strdup(statistics->rep->ToString().c_str());
+}
+
+void rocksdb_statistics_destroy(rocksdb_statistics_t* statistics) {
+  delete statistics;
+}
+
+// Transactions
+
+rocksdb_transactiondb_options_t* rocksdb_transactiondb_options_create() {
+  return new rocksdb_transactiondb_options_t;
+}
+
+void rocksdb_transactiondb_options_destroy(rocksdb_transactiondb_options_t* opt) {
+  delete opt;
+}
+
+void rocksdb_transactiondb_options_set_max_num_locks(rocksdb_transactiondb_options_t* opt,
+                                                     int64_t max_num_locks) {
+  opt->rep.max_num_locks = max_num_locks;
+}
+
+void rocksdb_transactiondb_options_set_num_stripes(rocksdb_transactiondb_options_t* opt,
+                                                   size_t num_stripes) {
+  opt->rep.num_stripes = num_stripes;
+}
+
+void rocksdb_transactiondb_options_set_transaction_lock_timeout(
+    rocksdb_transactiondb_options_t* opt, int64_t txn_lock_timeout) {
+  opt->rep.transaction_lock_timeout = txn_lock_timeout;
+}
+
+void rocksdb_transactiondb_options_set_default_lock_timeout(
+    rocksdb_transactiondb_options_t* opt, int64_t default_lock_timeout) {
+  opt->rep.default_lock_timeout = default_lock_timeout;
+}
+
+rocksdb_transaction_options_t* rocksdb_transaction_options_create() {
+  return new rocksdb_transaction_options_t;
+}
+
+void rocksdb_transaction_options_destroy(rocksdb_transaction_options_t* opt) {
+  delete opt;
+}
+
+void rocksdb_transaction_options_set_set_snapshot(rocksdb_transaction_options_t* opt,
+                                                  unsigned char v) {
+  opt->rep.set_snapshot = v;
+}
+
+void rocksdb_transaction_options_set_lock_timeout(rocksdb_transaction_options_t* opt,
+                                                  int64_t lock_timeout) {
+  opt->rep.lock_timeout = lock_timeout;
+}
+
+void rocksdb_transaction_options_set_expiration(rocksdb_transaction_options_t* opt,
+                                                int64_t expiration) {
+  opt->rep.expiration = expiration;
+}
+
+void rocksdb_transaction_options_set_max_write_batch_size(rocksdb_transaction_options_t* opt,
+                                                          size_t size) {
+  opt->rep.max_write_batch_size = size;
+}
+
+rocksdb_transactiondb_t* rocksdb_transactiondb_open_column_families(
+    const rocksdb_options_t* options, const rocksdb_transactiondb_options_t* txn_db_options,
+    const char* name, int num_column_families, const char** column_family_names,
+    const rocksdb_options_t** column_family_options,
+    rocksdb_column_family_handle_t** column_family_handles, rocksdb_error_t** errptr) {
+  std::vector<ColumnFamilyDescriptor> column_families =
+      GetColumnFamilies(num_column_families, column_family_names, column_family_options);
+  TransactionDB* txn_db;
+  std::vector<ColumnFamilyHandle*> handles;
+  if (SaveError(errptr, TransactionDB::Open(DBOptions(options->rep), txn_db_options->rep,
+                                            std::string(name), column_families, &handles,
+                                            &txn_db))) {
+    return nullptr;
+  }
+  SaveHandles(handles, column_family_handles);
+  rocksdb_transactiondb_t* result = new rocksdb_transactiondb_t;
+  result->rep = txn_db;
+  return result;
+}
+
+void rocksdb_transactiondb_close(rocksdb_transactiondb_t* txn_db) {
+  delete txn_db->rep;
+  delete txn_db;
+}
+
+char* rocksdb_transactiondb_get(rocksdb_transactiondb_t* txn_db,
+                                const rocksdb_readoptions_t* options, const char* key,
+                                size_t klen, size_t* vlen, rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = txn_db->rep->Get(GetReadOptions(options), Slice(key, klen), &tmp);
+  return GetResult(s, tmp, vlen, errptr);
+}
+
+char* rocksdb_transactiondb_get_cf(rocksdb_transactiondb_t* txn_db,
+                                   const rocksdb_readoptions_t* options,
+                                   rocksdb_column_family_handle_t* column_family,
+                                   const char* key, size_t klen, size_t* vlen,
+                                   rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = txn_db->rep->Get(GetReadOptions(options), column_family->rep, Slice(key, klen),
+                              &tmp);
+  return GetResult(s, tmp, vlen, errptr);
+}
+
+void rocksdb_transactiondb_put(rocksdb_transactiondb_t* txn_db,
+                               const rocksdb_writeoptions_t* options, const char* key,
+                               size_t klen, const char* val, size_t vlen,
+                               rocksdb_error_t** errptr) {
+  SaveError(errptr, txn_db->rep->Put(options->rep, Slice(key, klen), Slice(val, vlen)));
+}
+
+void rocksdb_transactiondb_put_cf(rocksdb_transactiondb_t* txn_db,
+                                  const rocksdb_writeoptions_t* options,
+                                  rocksdb_column_family_handle_t* column_family,
+                                  const char* key, size_t klen, const char* val, size_t vlen,
+                                  rocksdb_error_t** errptr) {
+  SaveError(errptr, txn_db->rep->Put(options->rep, column_family->rep, Slice(key, klen),
+                                     Slice(val, vlen)));
+}
+
+void rocksdb_transactiondb_merge(rocksdb_transactiondb_t* txn_db,
+                                 const rocksdb_writeoptions_t* options, const char* key,
+                                 size_t klen, const char* val, size_t vlen,
+                                 rocksdb_error_t** errptr) {
+  SaveError(errptr, txn_db->rep->Merge(options->rep, Slice(key, klen), Slice(val, vlen)));
+}
+
+void rocksdb_transactiondb_delete(rocksdb_transactiondb_t* txn_db,
+                                  const rocksdb_writeoptions_t* options, const char* key,
+                                  size_t klen, rocksdb_error_t** errptr) {
+  SaveError(errptr, txn_db->rep->Delete(options->rep, Slice(key, klen)));
+}
+
+void rocksdb_transactiondb_delete_cf(rocksdb_transactiondb_t* txn_db,
+                                     const rocksdb_writeoptions_t* options,
+                                     rocksdb_column_family_handle_t* column_family,
+                                     const char* key, size_t keylen, rocksdb_error_t** errptr) {
+  SaveError(errptr, txn_db->rep->Delete(options->rep, column_family->rep, Slice(key, keylen)));
+}
+
+rocksdb_transaction_t* rocksdb_transaction_begin(rocksdb_transactiondb_t* txn_db,
+                                                 const rocksdb_writeoptions_t* write_options,
+                                                 const rocksdb_transaction_options_t* txn_options,
+                                                 rocksdb_transaction_t* old_txn) {
+  Transaction* txn = txn_db->rep->BeginTransaction(
+      write_options->rep, txn_options->rep, old_txn == nullptr ? nullptr : old_txn->rep);
+  if (txn == nullptr) {
+    return nullptr;
+  }
+  if (old_txn != nullptr) {
+    old_txn->rep = txn;
+    return old_txn;
+  }
+  rocksdb_transaction_t* result = new rocksdb_transaction_t;
+  result->rep = txn;
+  return result;
+}
+
+void rocksdb_transaction_commit(rocksdb_transaction_t* txn, rocksdb_error_t** errptr) {
+  SaveError(errptr, txn->rep->Commit());
+}
+
+void rocksdb_transaction_rollback(rocksdb_transaction_t* txn, rocksdb_error_t** errptr) {
+  SaveError(errptr, txn->rep->Rollback());
+}
+
+void rocksdb_transaction_set_savepoint(rocksdb_transaction_t* txn) {
+  txn->rep->SetSavePoint();
+}
+
+void rocksdb_transaction_rollback_to_savepoint(rocksdb_transaction_t* txn,
+                                               rocksdb_error_t** errptr) {
+  SaveError(errptr, txn->rep->RollbackToSavePoint());
+}
+
+void rocksdb_transaction_destroy(rocksdb_transaction_t* txn) {
+  delete txn->rep;
+  delete txn;
+}
+
+char* rocksdb_transaction_get(rocksdb_transaction_t* txn, const rocksdb_readoptions_t* options,
+                              const char* key, size_t klen, size_t* vlen,
+                              rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = txn->rep->Get(GetReadOptions(options), Slice(key, klen), &tmp);
+  return GetResult(s, tmp, vlen, errptr);
+}
+
+char* rocksdb_transaction_get_cf(rocksdb_transaction_t* txn,
+                                 const rocksdb_readoptions_t* options,
+                                 rocksdb_column_family_handle_t* column_family,
+                                 const char* key, size_t klen, size_t* vlen,
+                                 rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = txn->rep->Get(GetReadOptions(options), column_family->rep, Slice(key, klen),
+                           &tmp);
+  return GetResult(s, tmp, vlen, errptr);
+}
+
+char* rocksdb_transaction_get_for_update(rocksdb_transaction_t* txn,
+                                         const rocksdb_readoptions_t* options,
+                                         const char* key, size_t klen, size_t* vlen,
+                                         unsigned char exclusive, rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = txn->rep->GetForUpdate(GetReadOptions(options), Slice(key, klen), &tmp,
+                                    exclusive);
+  return GetResult(s, tmp, vlen, errptr);
+}
+
+char* rocksdb_transaction_get_for_update_cf(rocksdb_transaction_t* txn,
+                                            const rocksdb_readoptions_t* options,
+                                            rocksdb_column_family_handle_t* column_family,
+                                            const char* key, size_t klen, size_t* vlen,
+                                            unsigned char exclusive, rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = txn->rep->GetForUpdate(GetReadOptions(options), column_family->rep,
+                                    Slice(key, klen), &tmp, exclusive);
+  return GetResult(s, tmp, vlen, errptr);
+}
+
+void rocksdb_transaction_put(rocksdb_transaction_t* txn, const char* key, size_t klen,
+                             const char* val, size_t vlen, rocksdb_error_t** errptr) {
+  SaveError(errptr, txn->rep->Put(Slice(key, klen), Slice(val, vlen)));
+}
+
+void rocksdb_transaction_put_cf(rocksdb_transaction_t* txn,
+                                rocksdb_column_family_handle_t* column_family, const char* key,
+                                size_t klen, const char* val, size_t vlen,
+                                rocksdb_error_t** errptr) {
+  SaveError(errptr, txn->rep->Put(column_family->rep, Slice(key, klen), Slice(val, vlen)));
+}
+
+void rocksdb_transaction_merge(rocksdb_transaction_t* txn, const char* key, size_t klen,
+                               const char* val, size_t vlen, rocksdb_error_t** errptr) {
+  SaveError(errptr, txn->rep->Merge(Slice(key, klen), Slice(val, vlen)));
+}
+
+void rocksdb_transaction_delete(rocksdb_transaction_t* txn, const char* key, size_t klen,
+                                rocksdb_error_t** errptr) {
+  SaveError(errptr, txn->rep->Delete(Slice(key, klen)));
+}
+
+void rocksdb_transaction_delete_cf(rocksdb_transaction_t* txn,
+                                   rocksdb_column_family_handle_t* column_family,
+                                   const char* key, size_t klen, rocksdb_error_t** errptr) {
+  SaveError(errptr, txn->rep->Delete(column_family->rep, Slice(key, klen)));
+}
+
+// Returns null if the transaction has no snapshot. The snapshot is owned by the transaction,
+// only the returned wrapper must be released with `rocksdb_transaction_snapshot_destroy`.
+const rocksdb_snapshot_t* rocksdb_transaction_get_snapshot(rocksdb_transaction_t* txn) {
+  const Snapshot* snapshot = txn->rep->GetSnapshot();
+  if (snapshot == nullptr) {
+    return nullptr;
+  }
+  rocksdb_snapshot_t* result = new rocksdb_snapshot_t;
+  result->rep = snapshot;
+  return result;
+}
+
+void rocksdb_transaction_snapshot_destroy(const rocksdb_snapshot_t* snapshot) {
+  delete snapshot;
+}
+
+// Optimistic transactions
+
+rocksdb_optimistictransactiondb_t* rocksdb_optimistictransactiondb_open_column_families(
+    const rocksdb_options_t* options, const char* name, int num_column_families,
+    const char** column_family_names, const rocksdb_options_t** column_family_options,
+    rocksdb_column_family_handle_t** column_family_handles, rocksdb_error_t** errptr) {
+  std::vector<ColumnFamilyDescriptor> column_families =
+      GetColumnFamilies(num_column_families, column_family_names, column_family_options);
+  OptimisticTransactionDB* otxn_db;
+  std::vector<ColumnFamilyHandle*> handles;
+  if (SaveError(errptr, OptimisticTransactionDB::Open(DBOptions(options->rep),
+                                                      std::string(name), column_families,
+                                                      &handles, &otxn_db))) {
+    return nullptr;
+  }
+  SaveHandles(handles, column_family_handles);
+  rocksdb_optimistictransactiondb_t* result = new rocksdb_optimistictransactiondb_t;
+  result->rep = otxn_db;
+  return result;
+}
+
+// The returned database is owned by the optimistic transaction database, it must be released
+// with `rocksdb_optimistictransactiondb_close_base_db` instead of `rocksdb_close`.
+rocksdb_t* rocksdb_optimistictransactiondb_get_base_db(
+    rocksdb_optimistictransactiondb_t* otxn_db) {
+  rocksdb_t* result = new rocksdb_t;
+  result->rep = otxn_db->rep->GetBaseDB();
+  return result;
+}
+
+void rocksdb_optimistictransactiondb_close_base_db(rocksdb_t* base_db) {
+  delete base_db;
+}
+
+void rocksdb_optimistictransactiondb_close(rocksdb_optimistictransactiondb_t* otxn_db) {
+  delete otxn_db->rep;
+  delete otxn_db;
+}
+
+rocksdb_optimistictransaction_options_t* rocksdb_optimistictransaction_options_create() {
+  return new rocksdb_optimistictransaction_options_t;
+}
+
+void rocksdb_optimistictransaction_options_destroy(
+    rocksdb_optimistictransaction_options_t* opt) {
+  delete opt;
+}
+
+void rocksdb_optimistictransaction_options_set_set_snapshot(
+    rocksdb_optimistictransaction_options_t* opt, unsigned char v) {
+  opt->rep.set_snapshot = v;
+}
+
+rocksdb_transaction_t* rocksdb_optimistictransaction_begin(
+    rocksdb_optimistictransactiondb_t* otxn_db, const rocksdb_writeoptions_t* write_options,
+    const rocksdb_optimistictransaction_options_t* otxn_options, rocksdb_transaction_t* old_txn) {
+  Transaction* txn = otxn_db->rep->BeginTransaction(
+      write_options->rep, otxn_options->rep, old_txn == nullptr ? nullptr : old_txn->rep);
+  if (txn == nullptr) {
+    return nullptr;
+  }
+  if (old_txn != nullptr) {
+    old_txn->rep = txn;
+    return old_txn;
+  }
+  rocksdb_transaction_t* result = new rocksdb_transaction_t;
+  result->rep = txn;
+  return result;
+}
+
+// Write batch with index
+
+// The batch orders the keys of the default column family with the comparator of `options`,
+// or byte-wise if they are null. Other column families use the comparator of their handle.
+rocksdb_writebatch_wi_t* rocksdb_writebatch_wi_create(const rocksdb_options_t* options,
+                                                      size_t reserved_bytes,
+                                                      unsigned char overwrite_keys) {
+  rocksdb_writebatch_wi_t* b = new rocksdb_writebatch_wi_t;
+  b->comparator = options == nullptr ? rocksdb::BytewiseComparator() : options->rep.comparator;
+  b->rep = new WriteBatchWithIndex(b->comparator, reserved_bytes, overwrite_keys);
+  return b;
+}
+
+void rocksdb_writebatch_wi_destroy(rocksdb_writebatch_wi_t* b) {
+  delete b->rep;
+  delete b;
+}
+
+void rocksdb_writebatch_wi_clear(rocksdb_writebatch_wi_t* b) {
+  b->rep->Clear();
+}
+
+int rocksdb_writebatch_wi_count(rocksdb_writebatch_wi_t* b) {
+  return b->rep->GetWriteBatch()->Count();
+}
+
+void rocksdb_writebatch_wi_put(rocksdb_writebatch_wi_t* b, const char* key, size_t klen,
+                               const char* val, size_t vlen) {
+  b->rep->Put(Slice(key, klen), Slice(val, vlen));
+}
+
+void rocksdb_writebatch_wi_put_cf(rocksdb_writebatch_wi_t* b,
+                                  rocksdb_column_family_handle_t* column_family,
+                                  const char* key, size_t klen, const char* val,
+                                  size_t vlen) {
+  b->rep->Put(column_family->rep, Slice(key, klen), Slice(val, vlen));
+}
+
+void rocksdb_writebatch_wi_merge(rocksdb_writebatch_wi_t* b, const char* key, size_t klen,
+                                 const char* val, size_t vlen) {
+  b->rep->Merge(Slice(key, klen), Slice(val, vlen));
+}
+
+void rocksdb_writebatch_wi_merge_cf(rocksdb_writebatch_wi_t* b,
+                                    rocksdb_column_family_handle_t* column_family,
+                                    const char* key, size_t klen, const char* val,
+                                    size_t vlen) {
+  b->rep->Merge(column_family->rep, Slice(key, klen), Slice(val, vlen));
+}
+
+void rocksdb_writebatch_wi_delete(rocksdb_writebatch_wi_t* b, const char* key, size_t klen) {
+  b->rep->Delete(Slice(key, klen));
+}
+
+void rocksdb_writebatch_wi_delete_cf(rocksdb_writebatch_wi_t* b,
+                                     rocksdb_column_family_handle_t* column_family,
+                                     const char* key, size_t klen) {
+  b->rep->Delete(column_family->rep, Slice(key, klen));
+}
+
+// Merging the keys of the batch with the ones of the database only works if both are in the
+// same order.
+static Status CheckComparator(rocksdb_writebatch_wi_t* wbwi, DB* db) {
+  const Comparator* db_comparator = db->DefaultColumnFamily()->GetComparator();
+  if (strcmp(wbwi->comparator->Name(), db_comparator->Name()) == 0) {
+    return Status::OK();
+  }
+  return Status::InvalidArgument("The batch orders keys with comparator " +
+                                 std::string(wbwi->comparator->Name()) +
+                                 ", the default column family with " +
+                                 db_comparator->Name());
+}
+
+char* rocksdb_writebatch_wi_get_from_batch(rocksdb_writebatch_wi_t* wbwi,
+                                           const rocksdb_options_t* options, const char* key,
+                                           size_t keylen, size_t* vallen,
+                                           rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = wbwi->rep->GetFromBatch(options->rep, Slice(key, keylen), &tmp);
+  return GetResult(s, tmp, vallen, errptr);
+}
+
+char* rocksdb_writebatch_wi_get_from_batch_cf(rocksdb_writebatch_wi_t* wbwi,
+                                              const rocksdb_options_t* options,
+                                              rocksdb_column_family_handle_t* column_family,
+                                              const char* key, size_t keylen, size_t* vallen,
+                                              rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = wbwi->rep->GetFromBatch(column_family->rep, options->rep, Slice(key, keylen),
+                                     &tmp);
+  return GetResult(s, tmp, vallen, errptr);
+}
+
+char* rocksdb_writebatch_wi_get_from_batch_and_db(rocksdb_writebatch_wi_t* wbwi, rocksdb_t* db,
+                                                  const rocksdb_readoptions_t* options,
+                                                  const char* key, size_t keylen,
+                                                  size_t* vallen, rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = CheckComparator(wbwi, db->rep);
+  if (s.ok()) {
+    s = wbwi->rep->GetFromBatchAndDB(db->rep, GetReadOptions(options), Slice(key, keylen),
+                                     &tmp);
+  }
+  return GetResult(s, tmp, vallen, errptr);
+}
+
+char* rocksdb_writebatch_wi_get_from_batch_and_db_cf(
+    rocksdb_writebatch_wi_t* wbwi, rocksdb_t* db, const rocksdb_readoptions_t* options,
+    rocksdb_column_family_handle_t* column_family, const char* key, size_t keylen,
+    size_t* vallen, rocksdb_error_t** errptr) {
+  std::string tmp;
+  Status s = wbwi->rep->GetFromBatchAndDB(db->rep, GetReadOptions(options), column_family->rep,
+                                          Slice(key, keylen), &tmp);
+  return GetResult(s, tmp, vallen, errptr);
+}
+
+void rocksdb_write_writebatch_wi(rocksdb_t* db, const rocksdb_writeoptions_t* options,
+                                 rocksdb_writebatch_wi_t* wbwi, rocksdb_error_t** errptr) {
+  SaveError(errptr, db->rep->Write(options->rep, wbwi->rep->GetWriteBatch()));
+}
+
+// The base iterator is taken over by the returned one. On failure null is returned and the
+// base iterator is destroyed.
+static rocksdb_iterator_t* WrapIteratorWithBase(Iterator* iter,
+                                                rocksdb_iterator_t* base_iterator,
+                                                rocksdb_error_t** errptr) {
+  if (iter == nullptr) {
+    SaveError(errptr, Status::InvalidArgument("The batch doesn't overwrite keys"));
+    rocksdb_iter_destroy(base_iterator);
+    return nullptr;
+  }
+  base_iterator->rep = iter;
+  return base_iterator;
+}
+
+rocksdb_iterator_t* rocksdb_writebatch_wi_create_iterator_with_base(
+    rocksdb_writebatch_wi_t* wbwi, rocksdb_t* db, rocksdb_iterator_t* base_iterator,
+    rocksdb_error_t** errptr) {
+  if (SaveError(errptr, CheckComparator(wbwi, db->rep))) {
+    rocksdb_iter_destroy(base_iterator);
+    return nullptr;
+  }
+  return WrapIteratorWithBase(wbwi->rep->NewIteratorWithBase(base_iterator->rep),
+                              base_iterator, errptr);
+}
+
+rocksdb_iterator_t* rocksdb_writebatch_wi_create_iterator_with_base_cf(
+    rocksdb_writebatch_wi_t* wbwi, rocksdb_iterator_t* base_iterator,
+    rocksdb_column_family_handle_t* column_family, rocksdb_error_t** errptr) {
+  return WrapIteratorWithBase(
+      wbwi->rep->NewIteratorWithBase(column_family->rep, base_iterator->rep), base_iterator,
+      errptr);
+}
+
+// Checkpoints
+
+rocksdb_checkpoint_t* rocksdb_checkpoint_object_create(rocksdb_t* db, rocksdb_error_t** errptr) {
+  Checkpoint* checkpoint;
+  if (SaveError(errptr, Checkpoint::Create(db->rep, &checkpoint))) {
+    return nullptr;
+  }
+  rocksdb_checkpoint_t* result = new rocksdb_checkpoint_t;
+  result->rep = checkpoint;
+  return result;
+}
+
+void rocksdb_checkpoint_create(rocksdb_checkpoint_t* checkpoint, const char* checkpoint_dir,
+                               rocksdb_error_t** errptr) {
+  SaveError(errptr, checkpoint->rep->CreateCheckpoint(std::string(checkpoint_dir)));
+}
+
+void rocksdb_checkpoint_object_destroy(rocksdb_checkpoint_t* checkpoint) {
+  delete checkpoint->rep;
+  delete checkpoint;
+}
+
+}  // end extern "C"
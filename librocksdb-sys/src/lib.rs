@@ -955,6 +955,654 @@ extern "C" {
                                            errptr: *mut *mut c_char);
 
     pub fn rocksdb_free(ptr: *mut c_void);
+
+    // The bindings from `c_ext.cc`, which return errors as `rocksdb_error_t`
+
+    pub fn rocksdb_error_code(err: *const rocksdb_error_t) -> c_int;
+
+    pub fn rocksdb_error_subcode(err: *const rocksdb_error_t) -> c_int;
+
+    pub fn rocksdb_error_message(err: *const rocksdb_error_t) -> *const c_char;
+
+    pub fn rocksdb_error_destroy(err: *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_open_column_families(options: *const rocksdb_options_t,
+                                            name: *const c_char,
+                                            num_column_families: c_int,
+                                            column_family_names: *const *const c_char,
+                                            column_family_options: *const *const rocksdb_options_t,
+                                            column_family_handles: *mut *mut rocksdb_column_family_handle_t,
+                                            errptr: *mut *mut rocksdb_error_t)
+                                            -> *mut rocksdb_t;
+
+    pub fn rocksdb_ext_open_for_read_only_column_families(options: *const rocksdb_options_t,
+                                                          name: *const c_char,
+                                                          num_column_families: c_int,
+                                                          column_family_names: *const *const c_char,
+                                                          column_family_options: *const *const rocksdb_options_t,
+                                                          column_family_handles: *mut *mut rocksdb_column_family_handle_t,
+                                                          error_if_log_file_exist: c_uchar,
+                                                          errptr: *mut *mut rocksdb_error_t)
+                                                          -> *mut rocksdb_t;
+
+    pub fn rocksdb_ext_list_column_families(options: *const rocksdb_options_t,
+                                            name: *const c_char,
+                                            lencf: *mut size_t,
+                                            errptr: *mut *mut rocksdb_error_t)
+                                            -> *mut *mut c_char;
+
+    pub fn rocksdb_ext_create_column_family(db: *mut rocksdb_t,
+                                            column_family_options: *const rocksdb_options_t,
+                                            column_family_name: *const c_char,
+                                            errptr: *mut *mut rocksdb_error_t)
+                                            -> *mut rocksdb_column_family_handle_t;
+
+    pub fn rocksdb_ext_drop_column_family(db: *mut rocksdb_t,
+                                          handle: *mut rocksdb_column_family_handle_t,
+                                          errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_put(db: *mut rocksdb_t,
+                           options: *const rocksdb_writeoptions_t,
+                           key: *const c_char,
+                           keylen: size_t,
+                           val: *const c_char,
+                           vallen: size_t,
+                           errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_put_cf(db: *mut rocksdb_t,
+                              options: *const rocksdb_writeoptions_t,
+                              column_family: *mut rocksdb_column_family_handle_t,
+                              key: *const c_char,
+                              keylen: size_t,
+                              val: *const c_char,
+                              vallen: size_t,
+                              errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_delete(db: *mut rocksdb_t,
+                              options: *const rocksdb_writeoptions_t,
+                              key: *const c_char,
+                              keylen: size_t,
+                              errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_delete_cf(db: *mut rocksdb_t,
+                                 options: *const rocksdb_writeoptions_t,
+                                 column_family: *mut rocksdb_column_family_handle_t,
+                                 key: *const c_char,
+                                 keylen: size_t,
+                                 errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_singledelete(db: *mut rocksdb_t,
+                                options: *const rocksdb_writeoptions_t,
+                                key: *const c_char,
+                                keylen: size_t,
+                                errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_singledelete_cf(db: *mut rocksdb_t,
+                                   options: *const rocksdb_writeoptions_t,
+                                   column_family: *mut rocksdb_column_family_handle_t,
+                                   key: *const c_char,
+                                   keylen: size_t,
+                                   errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_delete_range_cf(db: *mut rocksdb_t,
+                                   options: *const rocksdb_writeoptions_t,
+                                   column_family: *mut rocksdb_column_family_handle_t,
+                                   start_key: *const c_char,
+                                   start_key_len: size_t,
+                                   end_key: *const c_char,
+                                   end_key_len: size_t,
+                                   errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_merge(db: *mut rocksdb_t,
+                             options: *const rocksdb_writeoptions_t,
+                             key: *const c_char,
+                             keylen: size_t,
+                             val: *const c_char,
+                             vallen: size_t,
+                             errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_merge_cf(db: *mut rocksdb_t,
+                                options: *const rocksdb_writeoptions_t,
+                                column_family: *mut rocksdb_column_family_handle_t,
+                                key: *const c_char,
+                                keylen: size_t,
+                                val: *const c_char,
+                                vallen: size_t,
+                                errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_write(db: *mut rocksdb_t,
+                             options: *const rocksdb_writeoptions_t,
+                             batch: *mut rocksdb_writebatch_t,
+                             errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_get(db: *mut rocksdb_t,
+                           options: *const rocksdb_readoptions_t,
+                           key: *const c_char,
+                           keylen: size_t,
+                           vallen: *mut size_t,
+                           errptr: *mut *mut rocksdb_error_t)
+                           -> *mut c_char;
+
+    pub fn rocksdb_ext_get_cf(db: *mut rocksdb_t,
+                              options: *const rocksdb_readoptions_t,
+                              column_family: *mut rocksdb_column_family_handle_t,
+                              key: *const c_char,
+                              keylen: size_t,
+                              vallen: *mut size_t,
+                              errptr: *mut *mut rocksdb_error_t)
+                              -> *mut c_char;
+
+    pub fn rocksdb_ext_multi_get(db: *mut rocksdb_t,
+                                 options: *const rocksdb_readoptions_t,
+                                 num_keys: size_t,
+                                 keys_list: *const *const c_char,
+                                 keys_list_sizes: *const size_t,
+                                 values_list: *mut *mut c_char,
+                                 values_list_sizes: *mut size_t,
+                                 errs: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_multi_get_cf(db: *mut rocksdb_t,
+                                    options: *const rocksdb_readoptions_t,
+                                    column_families: *const *const rocksdb_column_family_handle_t,
+                                    num_keys: size_t,
+                                    keys_list: *const *const c_char,
+                                    keys_list_sizes: *const size_t,
+                                    values_list: *mut *mut c_char,
+                                    values_list_sizes: *mut size_t,
+                                    errs: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_livefiles_column_family_name(files: *const rocksdb_livefiles_t,
+                                                index: c_int)
+                                                -> *const c_char;
+
+    pub fn rocksdb_ext_delete_file_in_range(db: *mut rocksdb_t,
+                                            start_key: *const c_char,
+                                            start_key_len: size_t,
+                                            limit_key: *const c_char,
+                                            limit_key_len: size_t,
+                                            errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_delete_file_in_range_cf(db: *mut rocksdb_t,
+                                               column_family: *mut rocksdb_column_family_handle_t,
+                                               start_key: *const c_char,
+                                               start_key_len: size_t,
+                                               limit_key: *const c_char,
+                                               limit_key_len: size_t,
+                                               errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_writebatch_singledelete(batch: *mut rocksdb_writebatch_t,
+                                           key: *const c_char,
+                                           klen: size_t);
+
+    pub fn rocksdb_writebatch_singledelete_cf(batch: *mut rocksdb_writebatch_t,
+                                              column_family: *mut rocksdb_column_family_handle_t,
+                                              key: *const c_char,
+                                              klen: size_t);
+
+    pub fn rocksdb_writebatch_delete_range(batch: *mut rocksdb_writebatch_t,
+                                           start_key: *const c_char,
+                                           start_key_len: size_t,
+                                           end_key: *const c_char,
+                                           end_key_len: size_t);
+
+    pub fn rocksdb_writebatch_delete_range_cf(batch: *mut rocksdb_writebatch_t,
+                                              column_family: *mut rocksdb_column_family_handle_t,
+                                              start_key: *const c_char,
+                                              start_key_len: size_t,
+                                              end_key: *const c_char,
+                                              end_key_len: size_t);
+
+    pub fn rocksdb_ext_destroy_db(options: *const rocksdb_options_t,
+                                  name: *const c_char,
+                                  errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_flush(db: *mut rocksdb_t,
+                             options: *const rocksdb_flushoptions_t,
+                             errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_flush_cf(db: *mut rocksdb_t,
+                            options: *const rocksdb_flushoptions_t,
+                            column_family: *mut rocksdb_column_family_handle_t,
+                            errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_repair_db(options: *const rocksdb_options_t,
+                                 name: *const c_char,
+                                 errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_backup_engine_create_new_backup(be: *mut rocksdb_backup_engine_t,
+                                                       db: *mut rocksdb_t,
+                                                       errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_backup_engine_purge_old_backups(be: *mut rocksdb_backup_engine_t,
+                                                       num_backups_to_keep: uint32_t,
+                                                       errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_ext_backup_engine_restore_db_from_latest_backup(
+        be: *mut rocksdb_backup_engine_t,
+        db_dir: *const c_char,
+        wal_dir: *const c_char,
+        restore_options: *const rocksdb_restore_options_t,
+        errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_backup_engine_restore_db_from_backup(
+        be: *mut rocksdb_backup_engine_t,
+        db_dir: *const c_char,
+        wal_dir: *const c_char,
+        restore_options: *const rocksdb_restore_options_t,
+        backup_id: uint32_t,
+        errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_backup_engine_verify_backup(be: *mut rocksdb_backup_engine_t,
+                                               backup_id: uint32_t,
+                                               errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_backup_engine_delete_backup(be: *mut rocksdb_backup_engine_t,
+                                               backup_id: uint32_t,
+                                               errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_backupable_db_options_create(backup_dir: *const c_char)
+                                                -> *mut rocksdb_backupable_db_options_t;
+
+    pub fn rocksdb_backupable_db_options_set_share_table_files(
+        options: *mut rocksdb_backupable_db_options_t,
+        val: c_uchar);
+
+    pub fn rocksdb_backupable_db_options_set_sync(options: *mut rocksdb_backupable_db_options_t,
+                                                  val: c_uchar);
+
+    pub fn rocksdb_backupable_db_options_set_destroy_old_data(
+        options: *mut rocksdb_backupable_db_options_t,
+        val: c_uchar);
+
+    pub fn rocksdb_backupable_db_options_set_backup_log_files(
+        options: *mut rocksdb_backupable_db_options_t,
+        val: c_uchar);
+
+    pub fn rocksdb_backupable_db_options_set_backup_rate_limit(
+        options: *mut rocksdb_backupable_db_options_t,
+        limit: uint64_t);
+
+    pub fn rocksdb_backupable_db_options_set_restore_rate_limit(
+        options: *mut rocksdb_backupable_db_options_t,
+        limit: uint64_t);
+
+    pub fn rocksdb_backupable_db_options_destroy(options: *mut rocksdb_backupable_db_options_t);
+
+    pub fn rocksdb_backup_engine_open_opts(options: *const rocksdb_backupable_db_options_t,
+                                           backup_dir: *const c_char,
+                                           errptr: *mut *mut rocksdb_error_t)
+                                           -> *mut rocksdb_backup_engine_t;
+
+    pub fn rocksdb_backup_engine_create_new_backup_with_metadata(
+        be: *mut rocksdb_backup_engine_t,
+        db: *mut rocksdb_t,
+        app_metadata: *const c_char,
+        app_metadata_len: size_t,
+        flush_before_backup: c_uchar,
+        errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_backup_engine_info_app_metadata(info: *const rocksdb_backup_engine_info_t,
+                                                   index: c_int,
+                                                   len: *mut size_t)
+                                                   -> *const c_char;
+
+    pub fn rocksdb_options_get_statistics(opt: *const rocksdb_options_t)
+                                          -> *mut rocksdb_statistics_t;
+
+    pub fn rocksdb_statistics_to_string(statistics: *const rocksdb_statistics_t) -> *mut c_char;
+
+    pub fn rocksdb_statistics_destroy(statistics: *mut rocksdb_statistics_t);
+
+    // Transactions
+
+    pub fn rocksdb_transactiondb_options_create() -> *mut rocksdb_transactiondb_options_t;
+
+    pub fn rocksdb_transactiondb_options_destroy(opt: *mut rocksdb_transactiondb_options_t);
+
+    pub fn rocksdb_transactiondb_options_set_max_num_locks(
+        opt: *mut rocksdb_transactiondb_options_t,
+        max_num_locks: int64_t);
+
+    pub fn rocksdb_transactiondb_options_set_num_stripes(opt: *mut rocksdb_transactiondb_options_t,
+                                                         num_stripes: size_t);
+
+    pub fn rocksdb_transactiondb_options_set_transaction_lock_timeout(
+        opt: *mut rocksdb_transactiondb_options_t,
+        txn_lock_timeout: int64_t);
+
+    pub fn rocksdb_transactiondb_options_set_default_lock_timeout(
+        opt: *mut rocksdb_transactiondb_options_t,
+        default_lock_timeout: int64_t);
+
+    pub fn rocksdb_transaction_options_create() -> *mut rocksdb_transaction_options_t;
+
+    pub fn rocksdb_transaction_options_destroy(opt: *mut rocksdb_transaction_options_t);
+
+    pub fn rocksdb_transaction_options_set_set_snapshot(opt: *mut rocksdb_transaction_options_t,
+                                                        v: c_uchar);
+
+    pub fn rocksdb_transaction_options_set_lock_timeout(opt: *mut rocksdb_transaction_options_t,
+                                                        lock_timeout: int64_t);
+
+    pub fn rocksdb_transaction_options_set_expiration(opt: *mut rocksdb_transaction_options_t,
+                                                      expiration: int64_t);
+
+    pub fn rocksdb_transaction_options_set_max_write_batch_size(
+        opt: *mut rocksdb_transaction_options_t,
+        size: size_t);
+
+    pub fn rocksdb_transactiondb_open_column_families(options: *const rocksdb_options_t,
+                                                      txn_db_options: *const rocksdb_transactiondb_options_t,
+                                                      name: *const c_char,
+                                                      num_column_families: c_int,
+                                                      column_family_names: *const *const c_char,
+                                                      column_family_options: *const *const rocksdb_options_t,
+                                                      column_family_handles: *mut *mut rocksdb_column_family_handle_t,
+                                                      errptr: *mut *mut rocksdb_error_t)
+                                                      -> *mut rocksdb_transactiondb_t;
+
+    pub fn rocksdb_transactiondb_close(txn_db: *mut rocksdb_transactiondb_t);
+
+    pub fn rocksdb_transactiondb_get(txn_db: *mut rocksdb_transactiondb_t,
+                                     options: *const rocksdb_readoptions_t,
+                                     key: *const c_char,
+                                     klen: size_t,
+                                     vlen: *mut size_t,
+                                     errptr: *mut *mut rocksdb_error_t)
+                                     -> *mut c_char;
+
+    pub fn rocksdb_transactiondb_get_cf(txn_db: *mut rocksdb_transactiondb_t,
+                                        options: *const rocksdb_readoptions_t,
+                                        column_family: *mut rocksdb_column_family_handle_t,
+                                        key: *const c_char,
+                                        klen: size_t,
+                                        vlen: *mut size_t,
+                                        errptr: *mut *mut rocksdb_error_t)
+                                        -> *mut c_char;
+
+    pub fn rocksdb_transactiondb_put(txn_db: *mut rocksdb_transactiondb_t,
+                                     options: *const rocksdb_writeoptions_t,
+                                     key: *const c_char,
+                                     klen: size_t,
+                                     val: *const c_char,
+                                     vlen: size_t,
+                                     errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transactiondb_put_cf(txn_db: *mut rocksdb_transactiondb_t,
+                                        options: *const rocksdb_writeoptions_t,
+                                        column_family: *mut rocksdb_column_family_handle_t,
+                                        key: *const c_char,
+                                        klen: size_t,
+                                        val: *const c_char,
+                                        vlen: size_t,
+                                        errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transactiondb_merge(txn_db: *mut rocksdb_transactiondb_t,
+                                       options: *const rocksdb_writeoptions_t,
+                                       key: *const c_char,
+                                       klen: size_t,
+                                       val: *const c_char,
+                                       vlen: size_t,
+                                       errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transactiondb_delete(txn_db: *mut rocksdb_transactiondb_t,
+                                        options: *const rocksdb_writeoptions_t,
+                                        key: *const c_char,
+                                        klen: size_t,
+                                        errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transactiondb_delete_cf(txn_db: *mut rocksdb_transactiondb_t,
+                                           options: *const rocksdb_writeoptions_t,
+                                           column_family: *mut rocksdb_column_family_handle_t,
+                                           key: *const c_char,
+                                           keylen: size_t,
+                                           errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_begin(txn_db: *mut rocksdb_transactiondb_t,
+                                     write_options: *const rocksdb_writeoptions_t,
+                                     txn_options: *const rocksdb_transaction_options_t,
+                                     old_txn: *mut rocksdb_transaction_t)
+                                     -> *mut rocksdb_transaction_t;
+
+    pub fn rocksdb_transaction_commit(txn: *mut rocksdb_transaction_t,
+                                      errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_rollback(txn: *mut rocksdb_transaction_t,
+                                        errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_set_savepoint(txn: *mut rocksdb_transaction_t);
+
+    pub fn rocksdb_transaction_rollback_to_savepoint(txn: *mut rocksdb_transaction_t,
+                                                     errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_destroy(txn: *mut rocksdb_transaction_t);
+
+    pub fn rocksdb_transaction_get(txn: *mut rocksdb_transaction_t,
+                                   options: *const rocksdb_readoptions_t,
+                                   key: *const c_char,
+                                   klen: size_t,
+                                   vlen: *mut size_t,
+                                   errptr: *mut *mut rocksdb_error_t)
+                                   -> *mut c_char;
+
+    pub fn rocksdb_transaction_get_cf(txn: *mut rocksdb_transaction_t,
+                                      options: *const rocksdb_readoptions_t,
+                                      column_family: *mut rocksdb_column_family_handle_t,
+                                      key: *const c_char,
+                                      klen: size_t,
+                                      vlen: *mut size_t,
+                                      errptr: *mut *mut rocksdb_error_t)
+                                      -> *mut c_char;
+
+    pub fn rocksdb_transaction_get_for_update(txn: *mut rocksdb_transaction_t,
+                                              options: *const rocksdb_readoptions_t,
+                                              key: *const c_char,
+                                              klen: size_t,
+                                              vlen: *mut size_t,
+                                              exclusive: c_uchar,
+                                              errptr: *mut *mut rocksdb_error_t)
+                                              -> *mut c_char;
+
+    pub fn rocksdb_transaction_get_for_update_cf(txn: *mut rocksdb_transaction_t,
+                                                 options: *const rocksdb_readoptions_t,
+                                                 column_family: *mut rocksdb_column_family_handle_t,
+                                                 key: *const c_char,
+                                                 klen: size_t,
+                                                 vlen: *mut size_t,
+                                                 exclusive: c_uchar,
+                                                 errptr: *mut *mut rocksdb_error_t)
+                                                 -> *mut c_char;
+
+    pub fn rocksdb_transaction_put(txn: *mut rocksdb_transaction_t,
+                                   key: *const c_char,
+                                   klen: size_t,
+                                   val: *const c_char,
+                                   vlen: size_t,
+                                   errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_put_cf(txn: *mut rocksdb_transaction_t,
+                                      column_family: *mut rocksdb_column_family_handle_t,
+                                      key: *const c_char,
+                                      klen: size_t,
+                                      val: *const c_char,
+                                      vlen: size_t,
+                                      errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_merge(txn: *mut rocksdb_transaction_t,
+                                     key: *const c_char,
+                                     klen: size_t,
+                                     val: *const c_char,
+                                     vlen: size_t,
+                                     errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_delete(txn: *mut rocksdb_transaction_t,
+                                      key: *const c_char,
+                                      klen: size_t,
+                                      errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_delete_cf(txn: *mut rocksdb_transaction_t,
+                                         column_family: *mut rocksdb_column_family_handle_t,
+                                         key: *const c_char,
+                                         klen: size_t,
+                                         errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_transaction_get_snapshot(txn: *mut rocksdb_transaction_t)
+                                            -> *const rocksdb_snapshot_t;
+
+    pub fn rocksdb_transaction_snapshot_destroy(snapshot: *const rocksdb_snapshot_t);
+
+    // Optimistic transactions
+
+    pub fn rocksdb_optimistictransactiondb_open_column_families(
+        options: *const rocksdb_options_t,
+        name: *const c_char,
+        num_column_families: c_int,
+        column_family_names: *const *const c_char,
+        column_family_options: *const *const rocksdb_options_t,
+        column_family_handles: *mut *mut rocksdb_column_family_handle_t,
+        errptr: *mut *mut rocksdb_error_t)
+        -> *mut rocksdb_optimistictransactiondb_t;
+
+    pub fn rocksdb_optimistictransactiondb_get_base_db(
+        otxn_db: *mut rocksdb_optimistictransactiondb_t)
+        -> *mut rocksdb_t;
+
+    pub fn rocksdb_optimistictransactiondb_close_base_db(base_db: *mut rocksdb_t);
+
+    pub fn rocksdb_optimistictransactiondb_close(otxn_db: *mut rocksdb_optimistictransactiondb_t);
+
+    pub fn rocksdb_optimistictransaction_options_create()
+        -> *mut rocksdb_optimistictransaction_options_t;
+
+    pub fn rocksdb_optimistictransaction_options_destroy(
+        opt: *mut rocksdb_optimistictransaction_options_t);
+
+    pub fn rocksdb_optimistictransaction_options_set_set_snapshot(
+        opt: *mut rocksdb_optimistictransaction_options_t,
+        v: c_uchar);
+
+    pub fn rocksdb_optimistictransaction_begin(
+        otxn_db: *mut rocksdb_optimistictransactiondb_t,
+        write_options: *const rocksdb_writeoptions_t,
+        otxn_options: *const rocksdb_optimistictransaction_options_t,
+        old_txn: *mut rocksdb_transaction_t)
+        -> *mut rocksdb_transaction_t;
+
+    // Write batch with index
+
+    pub fn rocksdb_writebatch_wi_create(options: *const rocksdb_options_t,
+                                        reserved_bytes: size_t,
+                                        overwrite_keys: c_uchar)
+                                        -> *mut rocksdb_writebatch_wi_t;
+
+    pub fn rocksdb_writebatch_wi_destroy(b: *mut rocksdb_writebatch_wi_t);
+
+    pub fn rocksdb_writebatch_wi_clear(b: *mut rocksdb_writebatch_wi_t);
+
+    pub fn rocksdb_writebatch_wi_count(b: *mut rocksdb_writebatch_wi_t) -> c_int;
+
+    pub fn rocksdb_writebatch_wi_put(b: *mut rocksdb_writebatch_wi_t,
+                                     key: *const c_char,
+                                     klen: size_t,
+                                     val: *const c_char,
+                                     vlen: size_t);
+
+    pub fn rocksdb_writebatch_wi_put_cf(b: *mut rocksdb_writebatch_wi_t,
+                                        column_family: *mut rocksdb_column_family_handle_t,
+                                        key: *const c_char,
+                                        klen: size_t,
+                                        val: *const c_char,
+                                        vlen: size_t);
+
+    pub fn rocksdb_writebatch_wi_merge(b: *mut rocksdb_writebatch_wi_t,
+                                       key: *const c_char,
+                                       klen: size_t,
+                                       val: *const c_char,
+                                       vlen: size_t);
+
+    pub fn rocksdb_writebatch_wi_merge_cf(b: *mut rocksdb_writebatch_wi_t,
+                                          column_family: *mut rocksdb_column_family_handle_t,
+                                          key: *const c_char,
+                                          klen: size_t,
+                                          val: *const c_char,
+                                          vlen: size_t);
+
+    pub fn rocksdb_writebatch_wi_delete(b: *mut rocksdb_writebatch_wi_t,
+                                        key: *const c_char,
+                                        klen: size_t);
+
+    pub fn rocksdb_writebatch_wi_delete_cf(b: *mut rocksdb_writebatch_wi_t,
+                                           column_family: *mut rocksdb_column_family_handle_t,
+                                           key: *const c_char,
+                                           klen: size_t);
+
+    pub fn rocksdb_writebatch_wi_get_from_batch(wbwi: *mut rocksdb_writebatch_wi_t,
+                                                options: *const rocksdb_options_t,
+                                                key: *const c_char,
+                                                keylen: size_t,
+                                                vallen: *mut size_t,
+                                                errptr: *mut *mut rocksdb_error_t)
+                                                -> *mut c_char;
+
+    pub fn rocksdb_writebatch_wi_get_from_batch_cf(wbwi: *mut rocksdb_writebatch_wi_t,
+                                                   options: *const rocksdb_options_t,
+                                                   column_family: *mut rocksdb_column_family_handle_t,
+                                                   key: *const c_char,
+                                                   keylen: size_t,
+                                                   vallen: *mut size_t,
+                                                   errptr: *mut *mut rocksdb_error_t)
+                                                   -> *mut c_char;
+
+    pub fn rocksdb_writebatch_wi_get_from_batch_and_db(wbwi: *mut rocksdb_writebatch_wi_t,
+                                                       db: *mut rocksdb_t,
+                                                       options: *const rocksdb_readoptions_t,
+                                                       key: *const c_char,
+                                                       keylen: size_t,
+                                                       vallen: *mut size_t,
+                                                       errptr: *mut *mut rocksdb_error_t)
+                                                       -> *mut c_char;
+
+    pub fn rocksdb_writebatch_wi_get_from_batch_and_db_cf(
+        wbwi: *mut rocksdb_writebatch_wi_t,
+        db: *mut rocksdb_t,
+        options: *const rocksdb_readoptions_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        key: *const c_char,
+        keylen: size_t,
+        vallen: *mut size_t,
+        errptr: *mut *mut rocksdb_error_t)
+        -> *mut c_char;
+
+    pub fn rocksdb_write_writebatch_wi(db: *mut rocksdb_t,
+                                       options: *const rocksdb_writeoptions_t,
+                                       wbwi: *mut rocksdb_writebatch_wi_t,
+                                       errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_writebatch_wi_create_iterator_with_base(
+        wbwi: *mut rocksdb_writebatch_wi_t,
+        db: *mut rocksdb_t,
+        base_iterator: *mut rocksdb_iterator_t,
+        errptr: *mut *mut rocksdb_error_t)
+        -> *mut rocksdb_iterator_t;
+
+    pub fn rocksdb_writebatch_wi_create_iterator_with_base_cf(
+        wbwi: *mut rocksdb_writebatch_wi_t,
+        base_iterator: *mut rocksdb_iterator_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        errptr: *mut *mut rocksdb_error_t)
+        -> *mut rocksdb_iterator_t;
+
+    // Checkpoints
+
+    pub fn rocksdb_checkpoint_object_create(db: *mut rocksdb_t,
+                                            errptr: *mut *mut rocksdb_error_t)
+                                            -> *mut rocksdb_checkpoint_t;
+
+    pub fn rocksdb_checkpoint_create(checkpoint: *mut rocksdb_checkpoint_t,
+                                     checkpoint_dir: *const c_char,
+                                     errptr: *mut *mut rocksdb_error_t);
+
+    pub fn rocksdb_checkpoint_object_destroy(checkpoint: *mut rocksdb_checkpoint_t);
 }
 
 pub const rocksdb_block_based_table_index_type_binary_search: c_int = 0;
@@ -987,6 +1635,10 @@ pub enum rocksdb_backup_engine_t { }
 
 pub enum rocksdb_backup_engine_info_t { }
 
+pub enum rocksdb_backupable_db_options_t { }
+
+pub enum rocksdb_checkpoint_t { }
+
 pub enum rocksdb_restore_options_t { }
 
 pub enum rocksdb_cache_t { }
@@ -1044,3 +1696,21 @@ pub enum rocksdb_universal_compaction_options_t { }
 pub enum rocksdb_livefiles_t { }
 
 pub enum rocksdb_column_family_handle_t { }
+
+pub enum rocksdb_error_t { }
+
+pub enum rocksdb_statistics_t { }
+
+pub enum rocksdb_transactiondb_t { }
+
+pub enum rocksdb_transactiondb_options_t { }
+
+pub enum rocksdb_transaction_t { }
+
+pub enum rocksdb_transaction_options_t { }
+
+pub enum rocksdb_optimistictransactiondb_t { }
+
+pub enum rocksdb_optimistictransaction_options_t { }
+
+pub enum rocksdb_writebatch_wi_t { }
@@ -14,25 +14,55 @@
 //
 
 
-use {DB, Error};
+use {DB, Error, ErrorKind};
 use ffi;
 
-use libc::{c_int, uint32_t};
+use libc::{c_char, c_int, c_uchar, size_t, uint32_t, uint64_t};
 use std::ffi::CString;
 use std::path::Path;
+use std::slice;
 
 pub struct BackupEngine {
     inner: *mut ffi::rocksdb_backup_engine_t,
 }
 
+/// Options of a `BackupEngine`.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::backup::{BackupEngine, BackupEngineOptions};
+///
+/// let mut opts = BackupEngineOptions::default();
+/// opts.set_sync(false);
+/// opts.set_backup_log_files(false);
+/// opts.set_backup_rate_limit(64 * 1024 * 1024);
+/// let backup_engine = BackupEngine::open(&opts, "path/for/rocksdb/backup25").unwrap();
+/// ```
 pub struct BackupEngineOptions {
-    inner: *mut ffi::rocksdb_options_t,
+    inner: *mut ffi::rocksdb_backupable_db_options_t,
 }
 
 pub struct RestoreOptions {
     inner: *mut ffi::rocksdb_restore_options_t,
 }
 
+/// The metadata of a backup, as returned by `BackupEngine::get_backup_info`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupInfo {
+    /// ID of the backup, IDs are increasing.
+    pub backup_id: u32,
+    /// Time the backup was created, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// Size of the backup in bytes.
+    pub size: u64,
+    /// Number of files the backup consists of.
+    pub num_files: u32,
+    /// The metadata passed to `BackupEngine::create_new_backup_with_metadata`, empty
+    /// otherwise.
+    pub app_metadata: String,
+}
+
 impl BackupEngine {
     /// Open a backup engine with the specified options.
     pub fn open<P: AsRef<Path>>(opts: &BackupEngineOptions,
@@ -42,17 +72,22 @@ impl BackupEngine {
         let cpath = match CString::new(path.to_string_lossy().as_bytes()) {
             Ok(c) => c,
             Err(_) => {
-                return Err(Error::new("Failed to convert path to CString \
-                                       when opening backup engine"
-                    .to_owned()))
+                return Err(Error::with_kind(ErrorKind::CStringConversion,
+                                            "Failed to convert path to CString \
+                                             when opening backup engine"
+                                                .to_owned()))
             }
         };
 
         let be: *mut ffi::rocksdb_backup_engine_t;
-        unsafe { be = ffi_try!(ffi::rocksdb_backup_engine_open(opts.inner, cpath.as_ptr())) }
+        unsafe {
+            // The options are copied, the directory is only set on the copy
+            be = ffi_try!(ffi::rocksdb_backup_engine_open_opts(opts.inner, cpath.as_ptr()));
+        }
 
         if be.is_null() {
-            return Err(Error::new("Could not initialize backup engine.".to_owned()));
+            return Err(Error::with_kind(ErrorKind::NullHandle,
+                                        "Could not initialize backup engine.".to_owned()));
         }
 
         Ok(BackupEngine { inner: be })
@@ -60,22 +95,214 @@ impl BackupEngine {
 
     pub fn create_new_backup(&mut self, db: &DB) -> Result<(), Error> {
         unsafe {
-            ffi_try!(ffi::rocksdb_backup_engine_create_new_backup(self.inner, db.inner));
+            ffi_try!(ffi::rocksdb_ext_backup_engine_create_new_backup(self.inner, db.inner));
+            Ok(())
+        }
+    }
+
+    /// Creates a new backup like `create_new_backup`, recording `app_metadata` with it, which
+    /// is returned by `get_backup_info`.
+    ///
+    /// If `flush_before_backup` is false, the memtables aren't flushed and the WAL files are
+    /// backed up instead, unless `BackupEngineOptions::set_backup_log_files` disabled that.
+    pub fn create_new_backup_with_metadata(&mut self,
+                                           db: &DB,
+                                           flush_before_backup: bool,
+                                           app_metadata: &str)
+                                           -> Result<(), Error> {
+        let metadata_ptr = app_metadata.as_ptr() as *const c_char;
+        let metadata_len = app_metadata.len() as size_t;
+        let flush = flush_before_backup as c_uchar;
+        unsafe {
+            ffi_try!(ffi::rocksdb_backup_engine_create_new_backup_with_metadata(self.inner,
+                                                                                db.inner,
+                                                                                metadata_ptr,
+                                                                                metadata_len,
+                                                                                flush));
             Ok(())
         }
     }
 
     pub fn purge_old_backups(&mut self, num_backups_to_keep: usize) -> Result<(), Error> {
         unsafe {
-            ffi_try!(ffi::rocksdb_backup_engine_purge_old_backups(self.inner,
-                                                                  num_backups_to_keep as uint32_t));
+            let num_backups_to_keep = num_backups_to_keep as uint32_t;
+            ffi_try!(ffi::rocksdb_ext_backup_engine_purge_old_backups(self.inner,
+                                                                      num_backups_to_keep));
+            Ok(())
+        }
+    }
+
+    /// Restores the most recent backup into `db_dir`, with the WAL files in `wal_dir`.
+    ///
+    /// The database must not be open while it is restored. Usually both directories are the
+    /// same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::DB;
+    /// use rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
+    ///
+    /// let db_path = "path/for/rocksdb/storage24";
+    /// let backup_path = "path/for/rocksdb/backup24";
+    /// let mut backup_engine = BackupEngine::open(&BackupEngineOptions::default(), backup_path)
+    ///     .unwrap();
+    /// {
+    ///     let db = DB::open_default(db_path).unwrap();
+    ///     db.put(b"my key", b"my value").unwrap();
+    ///     backup_engine.create_new_backup(&db).unwrap();
+    /// }
+    ///
+    /// backup_engine.restore_from_latest_backup(db_path, db_path, &RestoreOptions::default())
+    ///     .unwrap();
+    /// let db = DB::open_default(db_path).unwrap();
+    /// assert_eq!(&*db.get(b"my key").unwrap().unwrap(), b"my value");
+    /// ```
+    pub fn restore_from_latest_backup<D: AsRef<Path>, W: AsRef<Path>>(&mut self,
+                                                                      db_dir: D,
+                                                                      wal_dir: W,
+                                                                      opts: &RestoreOptions)
+                                                                      -> Result<(), Error> {
+        let c_db_dir = try!(path_to_cstring(db_dir.as_ref()));
+        let c_wal_dir = try!(path_to_cstring(wal_dir.as_ref()));
+        unsafe {
+            ffi_try!(ffi::rocksdb_ext_backup_engine_restore_db_from_latest_backup(
+                self.inner,
+                c_db_dir.as_ptr(),
+                c_wal_dir.as_ptr(),
+                opts.inner));
+            Ok(())
+        }
+    }
+
+    /// Restores the backup with the given ID, like `restore_from_latest_backup`.
+    pub fn restore_from_backup<D: AsRef<Path>, W: AsRef<Path>>(&mut self,
+                                                               db_dir: D,
+                                                               wal_dir: W,
+                                                               opts: &RestoreOptions,
+                                                               backup_id: u32)
+                                                               -> Result<(), Error> {
+        let c_db_dir = try!(path_to_cstring(db_dir.as_ref()));
+        let c_wal_dir = try!(path_to_cstring(wal_dir.as_ref()));
+        unsafe {
+            ffi_try!(ffi::rocksdb_backup_engine_restore_db_from_backup(self.inner,
+                                                                       c_db_dir.as_ptr(),
+                                                                       c_wal_dir.as_ptr(),
+                                                                       opts.inner,
+                                                                       backup_id as uint32_t));
+            Ok(())
+        }
+    }
+
+    /// Returns the metadata of all backups, the oldest first.
+    pub fn get_backup_info(&self) -> Vec<BackupInfo> {
+        unsafe {
+            let info = ffi::rocksdb_backup_engine_get_backup_info(self.inner);
+            let count = ffi::rocksdb_backup_engine_info_count(info);
+            let backups = (0..count)
+                .map(|index| {
+                    BackupInfo {
+                        backup_id: ffi::rocksdb_backup_engine_info_backup_id(info, index) as u32,
+                        timestamp: ffi::rocksdb_backup_engine_info_timestamp(info, index) as i64,
+                        size: ffi::rocksdb_backup_engine_info_size(info, index) as u64,
+                        num_files: ffi::rocksdb_backup_engine_info_number_files(info, index) as
+                                   u32,
+                        app_metadata: app_metadata(info, index),
+                    }
+                })
+                .collect();
+            ffi::rocksdb_backup_engine_info_destroy(info);
+            backups
+        }
+    }
+
+    /// Checks that the files of a backup exist and have the expected sizes.
+    ///
+    /// The contents of the files aren't read, hence corrupted files are only detected when
+    /// the backup is restored.
+    pub fn verify_backup(&self, backup_id: u32) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_backup_engine_verify_backup(self.inner, backup_id as uint32_t));
+            Ok(())
+        }
+    }
+
+    /// Deletes the backup with the given ID. Files shared with other backups are kept.
+    pub fn delete_backup(&mut self, backup_id: u32) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_backup_engine_delete_backup(self.inner, backup_id as uint32_t));
             Ok(())
         }
     }
 }
 
 impl BackupEngineOptions {
-    //
+    /// Whether the SST files are shared between backups, so that each backup only copies the
+    /// files that are new since the previous one.
+    ///
+    /// Default: true
+    pub fn set_share_table_files(&mut self, share_table_files: bool) {
+        unsafe {
+            ffi::rocksdb_backupable_db_options_set_share_table_files(self.inner,
+                                                                     share_table_files as
+                                                                     c_uchar);
+        }
+    }
+
+    /// Whether the backup files are fsynced, which makes backups consistent after a crash of
+    /// the machine but slower to create.
+    ///
+    /// Default: true
+    pub fn set_sync(&mut self, sync: bool) {
+        unsafe {
+            ffi::rocksdb_backupable_db_options_set_sync(self.inner, sync as c_uchar);
+        }
+    }
+
+    /// Whether all existing backups are deleted when the backup engine is opened.
+    ///
+    /// Default: false
+    pub fn set_destroy_old_data(&mut self, destroy_old_data: bool) {
+        unsafe {
+            ffi::rocksdb_backupable_db_options_set_destroy_old_data(self.inner,
+                                                                    destroy_old_data as c_uchar);
+        }
+    }
+
+    /// Whether the WAL files are backed up. If not, the memtables are always flushed before a
+    /// backup, even if `flush_before_backup` is false, so that no writes are lost.
+    ///
+    /// Default: true
+    pub fn set_backup_log_files(&mut self, backup_log_files: bool) {
+        unsafe {
+            ffi::rocksdb_backupable_db_options_set_backup_log_files(self.inner,
+                                                                    backup_log_files as c_uchar);
+        }
+    }
+
+    /// Limits how many bytes per second are written while creating a backup, 0 means no
+    /// limit.
+    ///
+    /// Default: 0
+    pub fn set_backup_rate_limit(&mut self, bytes_per_second: u64) {
+        unsafe {
+            ffi::rocksdb_backupable_db_options_set_backup_rate_limit(self.inner,
+                                                                     bytes_per_second as
+                                                                     uint64_t);
+        }
+    }
+
+    /// Limits how many bytes per second are written while restoring a backup, 0 means no
+    /// limit.
+    ///
+    /// Default: 0
+    pub fn set_restore_rate_limit(&mut self, bytes_per_second: u64) {
+        unsafe {
+            ffi::rocksdb_backupable_db_options_set_restore_rate_limit(self.inner,
+                                                                      bytes_per_second as
+                                                                      uint64_t);
+        }
+    }
 }
 
 impl RestoreOptions {
@@ -89,7 +316,9 @@ impl RestoreOptions {
 impl Default for BackupEngineOptions {
     fn default() -> BackupEngineOptions {
         unsafe {
-            let opts = ffi::rocksdb_options_create();
+            // The directory is passed to `BackupEngine::open`
+            let backup_dir = CString::new("").unwrap();
+            let opts = ffi::rocksdb_backupable_db_options_create(backup_dir.as_ptr());
             if opts.is_null() {
                 panic!("Could not create RocksDB backup options".to_owned());
             }
@@ -121,7 +350,7 @@ impl Drop for BackupEngine {
 impl Drop for BackupEngineOptions {
     fn drop(&mut self) {
         unsafe {
-            ffi::rocksdb_options_destroy(self.inner);
+            ffi::rocksdb_backupable_db_options_destroy(self.inner);
         }
     }
 }
@@ -133,3 +362,22 @@ impl Drop for RestoreOptions {
         }
     }
 }
+
+fn path_to_cstring(path: &Path) -> Result<CString, Error> {
+    match CString::new(path.to_string_lossy().as_bytes()) {
+        Ok(c) => Ok(c),
+        Err(_) => {
+            Err(Error::with_kind(ErrorKind::CStringConversion,
+                                 format!("Failed to convert path to CString: {:?}", path)))
+        }
+    }
+}
+
+unsafe fn app_metadata(info: *const ffi::rocksdb_backup_engine_info_t, index: c_int) -> String {
+    let mut len: size_t = 0;
+    let ptr = ffi::rocksdb_backup_engine_info_app_metadata(info, index, &mut len);
+    if ptr.is_null() {
+        return String::new();
+    }
+    String::from_utf8_lossy(slice::from_raw_parts(ptr as *const u8, len as usize)).into_owned()
+}
@@ -0,0 +1,97 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Openable copies of a live database.
+//!
+//! A checkpoint is a directory with a consistent copy of a database that can be opened like
+//! any other. SST files are immutable, hence they are hard linked into it if it is on the same
+//! filesystem as the database, which makes creating a checkpoint cheap. The other files, e.g.
+//! the MANIFEST and the WAL, are copied.
+//!
+//! # Examples
+//!
+//! ```
+//! use rocksdb::{DB, Options};
+//! use rocksdb::checkpoint::Checkpoint;
+//!
+//! let db = DB::open_default("path/for/rocksdb/storage23").unwrap();
+//! db.put(b"my key", b"my value").unwrap();
+//!
+//! let checkpoint = Checkpoint::new(&db).unwrap();
+//! checkpoint.create_checkpoint("path/for/rocksdb/checkpoint23").unwrap();
+//!
+//! {
+//!     let copy = DB::open_default("path/for/rocksdb/checkpoint23").unwrap();
+//!     assert_eq!(&*copy.get(b"my key").unwrap().unwrap(), b"my value");
+//! }
+//! DB::destroy(&Options::default(), "path/for/rocksdb/checkpoint23").unwrap();
+//! ```
+
+use {DB, Error, ErrorKind};
+use ffi;
+
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// Creates checkpoints of a database, see the module documentation.
+pub struct Checkpoint<'a> {
+    inner: *mut ffi::rocksdb_checkpoint_t,
+    db: PhantomData<&'a DB>,
+}
+
+impl<'a> Checkpoint<'a> {
+    pub fn new(db: &'a DB) -> Result<Checkpoint<'a>, Error> {
+        let checkpoint: *mut ffi::rocksdb_checkpoint_t;
+        unsafe { checkpoint = ffi_try!(ffi::rocksdb_checkpoint_object_create(db.inner)) }
+
+        if checkpoint.is_null() {
+            return Err(Error::with_kind(ErrorKind::NullHandle,
+                                        "Could not create checkpoint object.".to_owned()));
+        }
+
+        Ok(Checkpoint {
+            inner: checkpoint,
+            db: PhantomData,
+        })
+    }
+
+    /// Creates a checkpoint of the database in the given directory, which must not exist yet.
+    pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let cpath = match CString::new(path.to_string_lossy().as_bytes()) {
+            Ok(c) => c,
+            Err(_) => {
+                return Err(Error::with_kind(ErrorKind::CStringConversion,
+                                            "Failed to convert path to CString \
+                                             when creating checkpoint"
+                                                .to_owned()))
+            }
+        };
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_checkpoint_create(self.inner, cpath.as_ptr()));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Drop for Checkpoint<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_checkpoint_object_destroy(self.inner);
+        }
+    }
+}
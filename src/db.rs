@@ -14,20 +14,28 @@
 //
 
 
-use {DB, Error, Options, WriteOptions, ColumnFamily};
+use {DB, Error, ErrorKind, FlushOptions, Options, ReadOnlyDB, ReadOptions, WriteOptions,
+     ColumnFamily, ColumnFamilyState};
 use ffi;
-use ffi_util::opt_bytes_to_ptr;
+use db_options::check_rtree_setup;
+use ffi_util::{error_from_ffi, opt_bytes_to_ptr};
+use properties::PropertyName;
+use statistics::Statistics;
+use rtree::{CoordinateType, ToMbb};
 
 use libc::{self, c_char, c_int, c_uchar, c_void, size_t};
 use std::collections::BTreeMap;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::fmt;
 use std::fs;
+use std::mem;
 use std::ops::Deref;
 use std::path::Path;
 use std::ptr;
 use std::slice;
 use std::str;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::Ordering as AtomicOrdering;
 
 const DEFAULT_COLUMN_FAMILY: &'static str = "default";
 
@@ -83,8 +91,36 @@ pub struct WriteBatch {
     inner: *mut ffi::rocksdb_writebatch_t,
 }
 
-pub struct ReadOptions {
-    inner: *mut ffi::rocksdb_readoptions_t,
+/// An atomic batch of write operations with an index of its keys, so that the pending writes
+/// can be read back before the batch is written.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{DB, WriteBatchWithIndex};
+///
+/// let db = DB::open_default("path/for/rocksdb/storage21").unwrap();
+/// db.put(b"key1", b"value1").unwrap();
+///
+/// let mut batch = WriteBatchWithIndex::default();
+/// batch.put(b"key2", b"value2").unwrap();
+/// assert_eq!(&*batch.get_from_batch(b"key2").unwrap().unwrap(), b"value2");
+/// assert!(batch.get_from_batch(b"key1").unwrap().is_none());
+/// assert_eq!(&*batch.get_from_batch_and_db(&db, b"key1").unwrap().unwrap(), b"value1");
+///
+/// db.write(batch).unwrap(); // Atomically commits the batch
+/// assert_eq!(&*db.get(b"key2").unwrap().unwrap(), b"value2");
+/// ```
+pub struct WriteBatchWithIndex {
+    inner: *mut ffi::rocksdb_writebatch_wi_t,
+    overwrite_key: bool,
+}
+
+/// A batch of write operations that `DB::write_opt` applies atomically, i.e. a `WriteBatch`
+/// or a `WriteBatchWithIndex`.
+pub trait WriteBatchLike {
+    #[doc(hidden)]
+    fn write_to(self, db: &DB, writeopts: &WriteOptions) -> Result<(), Error>;
 }
 
 /// A consistent view of the database at the point of creation.
@@ -100,6 +136,10 @@ pub struct ReadOptions {
 pub struct Snapshot<'a> {
     db: &'a DB,
     inner: *const ffi::rocksdb_snapshot_t,
+    // The snapshot of a transaction is released together with the transaction
+    owned_by_txn: bool,
+    // Read options that were set to the snapshot check that it wasn't released yet
+    alive: Arc<()>,
 }
 
 pub trait IteratorContext {
@@ -154,8 +194,13 @@ pub struct RtreeIteratorContext {
 ///     iter.prev();
 /// }
 /// ```
-pub struct DBRawIterator {
+pub struct DBRawIterator<'a> {
     inner: *mut ffi::rocksdb_iterator_t,
+    // The iterator refers to the read options (and the iterator context and upper bound set
+    // on them), hence they need to live as long as the iterator.
+    _readopts: ReadOptions,
+    // The iterator must not outlive the database (or the snapshot) it was created from.
+    db: &'a DB,
 }
 
 
@@ -186,8 +231,8 @@ pub struct DBRawIterator {
 ///     println!("Saw {:?} {:?}", key, value);
 /// }
 /// ```
-pub struct DBIterator {
-    raw: DBRawIterator,
+pub struct DBIterator<'a> {
+    raw: DBRawIterator<'a>,
     direction: Direction,
     just_seeked: bool,
 }
@@ -199,28 +244,58 @@ pub enum Direction {
 
 pub type KVBytes = (Box<[u8]>, Box<[u8]>);
 
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum OpenMode {
+    ReadWrite,
+    ReadOnly { error_if_log_file_exists: bool },
+    OptimisticTransaction,
+}
+
+/// The metadata of an SST file that is currently in use by the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveFile {
+    /// Name of the column family the file belongs to.
+    pub column_family_name: String,
+    /// Name of the file, relative to the database directory, e.g. `/000012.sst`.
+    pub name: String,
+    /// Size of the file in bytes.
+    pub size: usize,
+    /// Level of the file within the LSM tree.
+    pub level: i32,
+    /// Smallest user key within the file.
+    pub smallest_key: Vec<u8>,
+    /// Largest user key within the file.
+    pub largest_key: Vec<u8>,
+}
+
 pub enum IteratorMode<'a> {
     Start,
     End,
     From(&'a [u8], Direction),
 }
 
-impl DBRawIterator {
-    fn new(db: &DB, readopts: &ReadOptions) -> DBRawIterator {
+impl<'a> DBRawIterator<'a> {
+    fn new(db: &'a DB, readopts: ReadOptions) -> DBRawIterator<'a> {
         unsafe {
             DBRawIterator {
                 inner: ffi::rocksdb_create_iterator(db.inner, readopts.inner),
+                _readopts: readopts,
+                db: db,
             }
         }
     }
 
-    fn new_cf(db: &DB,
+    fn new_cf(db: &'a DB,
               cf_handle: ColumnFamily,
-              readopts: &ReadOptions)
-              -> Result<DBRawIterator, Error> {
+              readopts: ReadOptions)
+              -> Result<DBRawIterator<'a>, Error> {
         unsafe {
             Ok(DBRawIterator {
-                inner: ffi::rocksdb_create_iterator_cf(db.inner, readopts.inner, cf_handle.inner),
+                inner: ffi::rocksdb_create_iterator_cf(db.inner,
+                                                       readopts.inner,
+                                                       try!(cf_handle.handle())),
+                _readopts: readopts,
+                db: db,
             })
         }
     }
@@ -373,7 +448,7 @@ impl DBRawIterator {
     /// if the iterator's seek position is ever moved by any of the seek commands or the
     /// ``.next()`` and ``.previous()`` methods as the underlying buffer may be reused
     /// for something else or freed entirely.
-    pub unsafe fn key_inner<'a>(&'a self) -> Option<&'a [u8]> {
+    pub unsafe fn key_inner<'b>(&'b self) -> Option<&'b [u8]> {
         if self.valid() {
             let mut key_len: size_t = 0;
             let key_len_ptr: *mut size_t = &mut key_len;
@@ -399,7 +474,7 @@ impl DBRawIterator {
     /// if the iterator's seek position is ever moved by any of the seek commands or the
     /// ``.next()`` and ``.previous()`` methods as the underlying buffer may be reused
     /// for something else or freed entirely.
-    pub unsafe fn value_inner<'a>(&'a self) -> Option<&'a [u8]> {
+    pub unsafe fn value_inner<'b>(&'b self) -> Option<&'b [u8]> {
         if self.valid() {
             let mut val_len: size_t = 0;
             let val_len_ptr: *mut size_t = &mut val_len;
@@ -419,7 +494,18 @@ impl DBRawIterator {
     }
 }
 
-impl Drop for DBRawIterator {
+impl<'a> DBRawIterator<'a> {
+    /// Takes the raw iterator and its read options, the iterator needs to be destroyed by
+    /// the caller.
+    unsafe fn into_parts(self) -> (*mut ffi::rocksdb_iterator_t, ReadOptions) {
+        let inner = self.inner;
+        let readopts = ptr::read(&self._readopts);
+        mem::forget(self);
+        (inner, readopts)
+    }
+}
+
+impl<'a> Drop for DBRawIterator<'a> {
     fn drop(&mut self) {
         unsafe {
             ffi::rocksdb_iter_destroy(self.inner);
@@ -427,8 +513,8 @@ impl Drop for DBRawIterator {
     }
 }
 
-impl DBIterator {
-    fn new(db: &DB, readopts: &ReadOptions, mode: IteratorMode) -> DBIterator {
+impl<'a> DBIterator<'a> {
+    fn new(db: &'a DB, readopts: ReadOptions, mode: IteratorMode) -> DBIterator<'a> {
         let mut rv = DBIterator {
             raw: DBRawIterator::new(db, readopts),
             direction: Direction::Forward, // blown away by set_mode()
@@ -438,11 +524,11 @@ impl DBIterator {
         rv
     }
 
-    fn new_cf(db: &DB,
+    fn new_cf(db: &'a DB,
               cf_handle: ColumnFamily,
-              readopts: &ReadOptions,
+              readopts: ReadOptions,
               mode: IteratorMode)
-              -> Result<DBIterator, Error> {
+              -> Result<DBIterator<'a>, Error> {
         let mut rv = DBIterator {
             raw: try!(DBRawIterator::new_cf(db, cf_handle, readopts)),
             direction: Direction::Forward, // blown away by set_mode()
@@ -477,7 +563,7 @@ impl DBIterator {
     }
 }
 
-impl Iterator for DBIterator {
+impl<'a> Iterator for DBIterator<'a> {
     type Item = KVBytes;
 
     fn next(&mut self) -> Option<KVBytes> {
@@ -501,58 +587,92 @@ impl Iterator for DBIterator {
     }
 }
 
-impl Into<DBRawIterator> for DBIterator {
-    fn into(self) -> DBRawIterator {
+impl<'a> Into<DBRawIterator<'a>> for DBIterator<'a> {
+    fn into(self) -> DBRawIterator<'a> {
         self.raw
     }
 }
 
 impl<'a> Snapshot<'a> {
-    pub fn new(db: &DB) -> Snapshot {
+    pub fn new(db: &'a DB) -> Snapshot<'a> {
         let snapshot = unsafe { ffi::rocksdb_create_snapshot(db.inner) };
         Snapshot {
             db: db,
             inner: snapshot,
+            owned_by_txn: false,
+            alive: Arc::new(()),
+        }
+    }
+
+    /// Wraps the snapshot of a transaction, which stays owned by the transaction.
+    pub(crate) fn from_txn(db: &'a DB, inner: *const ffi::rocksdb_snapshot_t) -> Snapshot<'a> {
+        Snapshot {
+            db: db,
+            inner: inner,
+            owned_by_txn: true,
+            alive: Arc::new(()),
         }
     }
 
-    pub fn iterator(&self, mode: IteratorMode) -> DBIterator {
+    pub fn iterator<'b>(&'b self, mode: IteratorMode) -> DBIterator<'b> {
         let mut readopts = ReadOptions::default();
         readopts.set_snapshot(self);
-        DBIterator::new(self.db, &readopts, mode)
+        DBIterator::new(self.db, readopts, mode)
     }
 
-    pub fn iterator_cf(&self,
-                       cf_handle: ColumnFamily,
-                       mode: IteratorMode)
-                       -> Result<DBIterator, Error> {
+    pub fn iterator_cf<'b>(&'b self,
+                           cf_handle: ColumnFamily,
+                           mode: IteratorMode)
+                           -> Result<DBIterator<'b>, Error> {
         let mut readopts = ReadOptions::default();
         readopts.set_snapshot(self);
-        DBIterator::new_cf(self.db, cf_handle, &readopts, mode)
+        DBIterator::new_cf(self.db, cf_handle, readopts, mode)
     }
 
-    pub fn raw_iterator(&self) -> DBRawIterator {
+    pub fn raw_iterator<'b>(&'b self) -> DBRawIterator<'b> {
         let mut readopts = ReadOptions::default();
         readopts.set_snapshot(self);
-        DBRawIterator::new(self.db, &readopts)
+        DBRawIterator::new(self.db, readopts)
     }
 
-    pub fn raw_iterator_cf(&self,
-                       cf_handle: ColumnFamily)
-                       -> Result<DBRawIterator, Error> {
+    pub fn raw_iterator_cf<'b>(&'b self,
+                               cf_handle: ColumnFamily)
+                               -> Result<DBRawIterator<'b>, Error> {
         let mut readopts = ReadOptions::default();
         readopts.set_snapshot(self);
-        DBRawIterator::new_cf(self.db, cf_handle, &readopts)
+        DBRawIterator::new_cf(self.db, cf_handle, readopts)
     }
 
-    pub fn rtree_iterator(&self, mbb: &[u8]) -> DBIterator {
-        let mut opts = ReadOptions::default();
-        let context = RtreeIteratorContext::new(&mbb);
-        opts.set_iterator_context(&context);
-        opts.set_snapshot(self);
-
+    /// Returns an iterator over the entries of the column family named `rtree` that intersect
+    /// with the given query, see `DB::rtree_iterator`.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if there is no column family named `rtree`, or if the query doesn't match its
+    ///   number of dimensions or coordinate type.
+    pub fn rtree_iterator<'b, Q: ToMbb + ?Sized>(&'b self, mbb: &Q) -> DBIterator<'b> {
         let rtree_cf = self.db.cf_handle("rtree").unwrap();
-        DBIterator::new_cf(self.db, rtree_cf, &opts, IteratorMode::Start).unwrap()
+        self.rtree_iterator_cf(rtree_cf, mbb).unwrap()
+    }
+
+    /// Returns an iterator over the entries of an R-tree column family that intersect with
+    /// the given query.
+    pub fn rtree_iterator_cf<'b, Q: ToMbb + ?Sized>(&'b self,
+                                                    cf_handle: ColumnFamily,
+                                                    mbb: &Q)
+                                                    -> Result<DBIterator<'b>, Error> {
+        self.rtree_iterator_cf_opt(cf_handle, mbb, ReadOptions::default())
+    }
+
+    /// Like `Snapshot::rtree_iterator_cf`, but with additional read options, e.g. an upper
+    /// bound.
+    pub fn rtree_iterator_cf_opt<'b, Q: ToMbb + ?Sized>(&'b self,
+                                                        cf_handle: ColumnFamily,
+                                                        mbb: &Q,
+                                                        mut readopts: ReadOptions)
+                                                        -> Result<DBIterator<'b>, Error> {
+        readopts.set_snapshot(self);
+        self.db.rtree_iterator_cf_opt(cf_handle, mbb, readopts)
     }
 
     pub fn get(&self, key: &[u8]) -> Result<Option<DBVector>, Error> {
@@ -569,12 +689,31 @@ impl<'a> Snapshot<'a> {
         readopts.set_snapshot(self);
         self.db.get_cf_opt(cf, key, &readopts)
     }
+
+    pub fn multi_get<K: AsRef<[u8]>>(&self, keys: &[K]) -> Vec<Result<Option<DBVector>, Error>> {
+        let mut readopts = ReadOptions::default();
+        readopts.set_snapshot(self);
+        self.db.multi_get_opt(keys, &readopts)
+    }
+
+    pub fn multi_get_cf<K: AsRef<[u8]>>(&self,
+                                        cf: ColumnFamily,
+                                        keys: &[K])
+                                        -> Vec<Result<Option<DBVector>, Error>> {
+        let mut readopts = ReadOptions::default();
+        readopts.set_snapshot(self);
+        self.db.multi_get_cf_opt(cf, keys, &readopts)
+    }
 }
 
 impl<'a> Drop for Snapshot<'a> {
     fn drop(&mut self) {
         unsafe {
-            ffi::rocksdb_release_snapshot(self.db.inner, self.inner);
+            if self.owned_by_txn {
+                ffi::rocksdb_transaction_snapshot_destroy(self.inner);
+            } else {
+                ffi::rocksdb_release_snapshot(self.db.inner, self.inner);
+            }
         }
     }
 }
@@ -586,7 +725,10 @@ impl IteratorContext for RtreeIteratorContext {
 }
 
 impl RtreeIteratorContext {
-    pub fn new(mbb: &[u8]) -> RtreeIteratorContext {
+    /// Creates a context for an R-tree query, either from an `rtree::RtreeQuery` or from an
+    /// already encoded minimum bounding box.
+    pub fn new<Q: ToMbb + ?Sized>(query: &Q) -> RtreeIteratorContext {
+        let mbb = query.to_mbb();
         unsafe {
             let context = ffi::rocksdb_create_rtree_iterator_context(mbb.as_ptr() as *const c_char,
                                                                      mbb.len() as size_t);
@@ -618,99 +760,155 @@ impl DB {
 
     /// Open a database with specified database options and column family options.
     ///
-    /// A column family must be created first by calling `DB::create_cf`.
-    ///
-    /// # Panics
+    /// A column family must be created first by calling `DB::create_cf`. All column families
+    /// that exist need to be named, `DB::open_all_cf` opens them without naming them.
     ///
-    /// * Panics if the column family doesn't exist.
+    /// Returns an error if a column family doesn't exist or isn't named, or if the options of
+    /// an R-tree column family are incomplete, see `Options::for_rtree`.
     pub fn open_cf<P: AsRef<Path>>(opts: &Options,
                                    path: P,
                                    cfs: &[&str],
                                    cf_opts: &[&Options])
                                    -> Result<DB, Error> {
+        DB::open_cf_with_mode(opts, path, cfs, cf_opts, OpenMode::ReadWrite)
+    }
+
+    fn open_cf_with_mode<P: AsRef<Path>>(opts: &Options,
+                                         path: P,
+                                         cfs: &[&str],
+                                         cf_opts: &[&Options],
+                                         mode: OpenMode)
+                                         -> Result<DB, Error> {
         let path = path.as_ref();
-        let cpath = match CString::new(path.to_string_lossy().as_bytes()) {
-            Ok(c) => c,
-            Err(_) => {
-                return Err(Error::new("Failed to convert path to CString \
-                                       when opening DB."
-                    .to_owned()))
+        let mut optimistic_txn_db = ptr::null_mut();
+        let (db, cf_map) = match mode {
+            OpenMode::ReadWrite => {
+                let open = |args: &OpenArgs| unsafe {
+                    Ok(ffi_try!(ffi::rocksdb_ext_open_column_families(opts.inner,
+                                                                      args.path,
+                                                                      args.num_cfs,
+                                                                      args.cf_names,
+                                                                      args.cf_opts,
+                                                                      args.cf_handles)))
+                };
+                try!(open_cf_raw(opts, path, cfs, cf_opts, true, open, ffi::rocksdb_close))
+            }
+            OpenMode::ReadOnly { error_if_log_file_exists } => {
+                let open = |args: &OpenArgs| unsafe {
+                    Ok(ffi_try!(ffi::rocksdb_ext_open_for_read_only_column_families(
+                        opts.inner,
+                        args.path,
+                        args.num_cfs,
+                        args.cf_names,
+                        args.cf_opts,
+                        args.cf_handles,
+                        error_if_log_file_exists as c_uchar)))
+                };
+                // A read-only database must exist already
+                try!(open_cf_raw(opts, path, cfs, cf_opts, false, open, ffi::rocksdb_close))
+            }
+            OpenMode::OptimisticTransaction => {
+                let open = |args: &OpenArgs| unsafe {
+                    Ok(ffi_try!(ffi::rocksdb_optimistictransactiondb_open_column_families(
+                        opts.inner,
+                        args.path,
+                        args.num_cfs,
+                        args.cf_names,
+                        args.cf_opts,
+                        args.cf_handles)))
+                };
+                let close = ffi::rocksdb_optimistictransactiondb_close;
+                let (txn_db, cf_map) =
+                    try!(open_cf_raw(opts, path, cfs, cf_opts, true, open, close));
+                optimistic_txn_db = txn_db;
+                (unsafe { ffi::rocksdb_optimistictransactiondb_get_base_db(txn_db) }, cf_map)
             }
         };
 
-        if let Err(e) = fs::create_dir_all(&path) {
-            return Err(Error::new(format!("Failed to create RocksDB\
-                                           directory: `{:?}`.",
-                                          e)));
-        }
-
-        if cfs.len() != cf_opts.len() {
-            return Err(Error::new(format!("cfs.len() and cf_opts.len() do not match.")));
-        }
-
-        let mut cfs_v = cfs.to_vec();
-        let mut cf_opts_v = cf_opts.to_vec();
-        // Always open the default column family
-        if !cfs_v.contains(&DEFAULT_COLUMN_FAMILY) {
-            cfs_v.push(DEFAULT_COLUMN_FAMILY);
-            cf_opts_v.push(opts);
-        }
-
-        // We need to store our CStrings in an intermediate vector
-        // so that their pointers remain valid.
-        let c_cfs: Vec<CString> = cfs_v.iter()
-            .map(|cf| CString::new(cf.as_bytes()).unwrap())
-            .collect();
-
-        let cfnames: Vec<*const _> = c_cfs.iter()
-            .map(|cf| cf.as_ptr())
-            .collect();
-
-        // These handles will be populated by DB.
-        let mut cfhandles: Vec<_> = cfs_v.iter()
-            .map(|_| ptr::null_mut())
-            .collect();
+        Ok(DB {
+            inner: db,
+            cfs: RwLock::new(cf_map),
+            dropped_cfs: Mutex::new(Vec::new()),
+            path: path.to_path_buf(),
+            statistics: unsafe { ffi::rocksdb_options_get_statistics(opts.inner) },
+            optimistic_txn_db: optimistic_txn_db,
+        })
+    }
 
-        let cfopts: Vec<_> =
-            cf_opts_v.iter().map(|x| x.inner as *const _).collect();
+    /// Open a database with all its column families, the options of every column family
+    /// including the default one are returned by the given closure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{DB, Options};
+    ///
+    /// let path = "path/for/rocksdb/storage18";
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// {
+    ///     let db = DB::open(&opts, path).unwrap();
+    ///     db.create_cf("cf1", &Options::default()).unwrap();
+    ///     db.create_rtree_cf("rtree", 2).unwrap();
+    /// }
+    ///
+    /// let db = DB::open_all_cf(&opts, path, |name| match name {
+    ///     "rtree" => Options::for_rtree(2),
+    ///     _ => Options::default(),
+    /// }).unwrap();
+    /// assert!(db.cf_handle("cf1").is_some());
+    /// assert!(db.cf_handle("rtree").is_some());
+    /// ```
+    pub fn open_all_cf<P, F>(opts: &Options, path: P, mut cf_opts: F) -> Result<DB, Error>
+        where P: AsRef<Path>,
+              F: FnMut(&str) -> Options
+    {
+        let path = path.as_ref();
+        let cfs = match DB::list_cf(opts, path) {
+            Ok(cfs) => cfs,
+            // It's a new database
+            Err(_) if !path.join("CURRENT").exists() => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let cf_opts_v: Vec<Options> = cfs.iter().map(|cf| cf_opts(cf)).collect();
+        let cfs_refs: Vec<&str> = cfs.iter().map(|cf| cf.as_str()).collect();
+        let cf_opts_refs: Vec<&Options> = cf_opts_v.iter().collect();
+        DB::open_cf(opts, path, &cfs_refs, &cf_opts_refs)
+    }
 
-        let db: *mut ffi::rocksdb_t;
+    /// Returns the names of all column families of a database, including the default one.
+    pub fn list_cf<P: AsRef<Path>>(opts: &Options, path: P) -> Result<Vec<String>, Error> {
+        let cpath = match CString::new(path.as_ref().to_string_lossy().as_bytes()) {
+            Ok(c) => c,
+            Err(_) => {
+                return Err(Error::with_kind(ErrorKind::CStringConversion,
+                                            "Failed to convert path to CString \
+                                             when listing column families."
+                                                .to_owned()))
+            }
+        };
         unsafe {
-            db = ffi_try!(ffi::rocksdb_open_column_families(opts.inner,
-                                                            cpath.as_ptr() as *const _,
-                                                            cfs_v.len() as c_int,
-                                                            cfnames.as_ptr() as *const _,
-                                                            cfopts.as_ptr(),
-                                                            cfhandles.as_mut_ptr()));
-        }
-
-        for handle in &cfhandles {
-            if handle.is_null() {
-                return Err(Error::new("Received null column family handle from DB."
-                                      .to_owned()));
+            let mut length: size_t = 0;
+            let list = ffi_try!(ffi::rocksdb_ext_list_column_families(opts.inner,
+                                                                      cpath.as_ptr(),
+                                                                      &mut length));
+            if list.is_null() {
+                return Err(Error::with_kind(ErrorKind::NullHandle,
+                                            "Could not list column families.".to_owned()));
             }
+            let names = slice::from_raw_parts(list, length)
+                .iter()
+                .map(|&name| c_str_to_string(name))
+                .collect();
+            ffi::rocksdb_list_column_families_destroy(list, length);
+            Ok(names)
         }
-
-        let mut cf_map = BTreeMap::new();
-        for (n, h) in cfs_v.iter().zip(cfhandles) {
-            cf_map.insert(n.to_string(), ColumnFamily { inner: h });
-        }
-
-        if db.is_null() {
-            return Err(Error::new("Could not initialize database.".to_owned()));
-        }
-
-        Ok(DB {
-            inner: db,
-            cfs: cf_map,
-            path: path.to_path_buf(),
-        })
     }
 
     pub fn destroy<P: AsRef<Path>>(opts: &Options, path: P) -> Result<(), Error> {
         let cpath = CString::new(path.as_ref().to_string_lossy().as_bytes()).unwrap();
         unsafe {
-            ffi_try!(ffi::rocksdb_destroy_db(opts.inner, cpath.as_ptr()));
+            ffi_try!(ffi::rocksdb_ext_destroy_db(opts.inner, cpath.as_ptr()));
         }
         Ok(())
     }
@@ -718,7 +916,7 @@ impl DB {
     pub fn repair<P: AsRef<Path>>(opts: Options, path: P) -> Result<(), Error> {
         let cpath = CString::new(path.as_ref().to_string_lossy().as_bytes()).unwrap();
         unsafe {
-            ffi_try!(ffi::rocksdb_repair_db(opts.inner, cpath.as_ptr()));
+            ffi_try!(ffi::rocksdb_ext_repair_db(opts.inner, cpath.as_ptr()));
         }
         Ok(())
     }
@@ -727,18 +925,18 @@ impl DB {
         &self.path.as_path()
     }
 
-    pub fn write_opt(&self, batch: WriteBatch, writeopts: &WriteOptions) -> Result<(), Error> {
-        unsafe {
-            ffi_try!(ffi::rocksdb_write(self.inner, writeopts.inner, batch.inner));
-        }
-        Ok(())
+    pub fn write_opt<B: WriteBatchLike>(&self,
+                                        batch: B,
+                                        writeopts: &WriteOptions)
+                                        -> Result<(), Error> {
+        batch.write_to(self, writeopts)
     }
 
-    pub fn write(&self, batch: WriteBatch) -> Result<(), Error> {
+    pub fn write<B: WriteBatchLike>(&self, batch: B) -> Result<(), Error> {
         self.write_opt(batch, &WriteOptions::default())
     }
 
-    pub fn write_without_wal(&self, batch: WriteBatch) -> Result<(), Error> {
+    pub fn write_without_wal<B: WriteBatchLike>(&self, batch: B) -> Result<(), Error> {
         let mut wo = WriteOptions::new();
         wo.disable_wal(true);
         self.write_opt(batch, &wo)
@@ -746,21 +944,17 @@ impl DB {
 
     pub fn get_opt(&self, key: &[u8], readopts: &ReadOptions) -> Result<Option<DBVector>, Error> {
         if readopts.inner.is_null() {
-            return Err(Error::new("Unable to create RocksDB read options. \
-                                   This is a fairly trivial call, and its \
-                                   failure may be indicative of a \
-                                   mis-compiled or mis-loaded RocksDB \
-                                   library."
-                .to_owned()));
+            return Err(null_readopts_error());
         }
+        try!(readopts.check_snapshot());
 
         unsafe {
             let mut val_len: size_t = 0;
-            let val = ffi_try!(ffi::rocksdb_get(self.inner,
-                                                readopts.inner,
-                                                key.as_ptr() as *const c_char,
-                                                key.len() as size_t,
-                                                &mut val_len)) as *mut u8;
+            let val = ffi_try!(ffi::rocksdb_ext_get(self.inner,
+                                                    readopts.inner,
+                                                    key.as_ptr() as *const c_char,
+                                                    key.len() as size_t,
+                                                    &mut val_len)) as *mut u8;
             if val.is_null() {
                 Ok(None)
             } else {
@@ -780,22 +974,18 @@ impl DB {
                       readopts: &ReadOptions)
                       -> Result<Option<DBVector>, Error> {
         if readopts.inner.is_null() {
-            return Err(Error::new("Unable to create RocksDB read options. \
-                                   This is a fairly trivial call, and its \
-                                   failure may be indicative of a \
-                                   mis-compiled or mis-loaded RocksDB \
-                                   library."
-                .to_owned()));
+            return Err(null_readopts_error());
         }
+        try!(readopts.check_snapshot());
 
         unsafe {
             let mut val_len: size_t = 0;
-            let val = ffi_try!(ffi::rocksdb_get_cf(self.inner,
-                                                   readopts.inner,
-                                                   cf.inner,
-                                                   key.as_ptr() as *const c_char,
-                                                   key.len() as size_t,
-                                                   &mut val_len)) as *mut u8;
+            let val = ffi_try!(ffi::rocksdb_ext_get_cf(self.inner,
+                                                       readopts.inner,
+                                                       try!(cf.handle()),
+                                                       key.as_ptr() as *const c_char,
+                                                       key.len() as size_t,
+                                                       &mut val_len)) as *mut u8;
             if val.is_null() {
                 Ok(None)
             } else {
@@ -811,91 +1001,274 @@ impl DB {
         self.get_cf_opt(cf, key, &ReadOptions::default())
     }
 
-    pub fn create_cf(&mut self,
-                     name: &str,
-                     opts: &Options)
-                     -> Result<ColumnFamily, Error> {
+    /// Returns the values associated with the given keys, in the same order as the keys.
+    ///
+    /// All lookups are done with a single call into RocksDB, which is faster than calling
+    /// `get_opt` for every key.
+    pub fn multi_get_opt<K: AsRef<[u8]>>(&self,
+                                         keys: &[K],
+                                         readopts: &ReadOptions)
+                                         -> Vec<Result<Option<DBVector>, Error>> {
+        if readopts.inner.is_null() {
+            return keys.iter().map(|_| Err(null_readopts_error())).collect();
+        }
+        if let Err(e) = readopts.check_snapshot() {
+            return keys.iter().map(|_| Err(e.clone())).collect();
+        }
+
+        let keys_list: Vec<*const c_char> =
+            keys.iter().map(|key| key.as_ref().as_ptr() as *const c_char).collect();
+        let keys_list_sizes: Vec<size_t> =
+            keys.iter().map(|key| key.as_ref().len() as size_t).collect();
+        let mut values_list: Vec<*mut c_char> = vec![ptr::null_mut(); keys.len()];
+        let mut values_list_sizes: Vec<size_t> = vec![0; keys.len()];
+        let mut errs: Vec<*mut ffi::rocksdb_error_t> = vec![ptr::null_mut(); keys.len()];
+        unsafe {
+            ffi::rocksdb_ext_multi_get(self.inner,
+                                       readopts.inner,
+                                       keys.len() as size_t,
+                                       keys_list.as_ptr(),
+                                       keys_list_sizes.as_ptr(),
+                                       values_list.as_mut_ptr(),
+                                       values_list_sizes.as_mut_ptr(),
+                                       errs.as_mut_ptr());
+        }
+        multi_get_results(values_list, values_list_sizes, errs)
+    }
+
+    /// Returns the values associated with the given keys, see `DB::multi_get_opt`.
+    pub fn multi_get<K: AsRef<[u8]>>(&self, keys: &[K]) -> Vec<Result<Option<DBVector>, Error>> {
+        self.multi_get_opt(keys, &ReadOptions::default())
+    }
+
+    /// Returns the values associated with the given keys within a column family, in the same
+    /// order as the keys.
+    pub fn multi_get_cf_opt<K: AsRef<[u8]>>(&self,
+                                            cf: ColumnFamily,
+                                            keys: &[K],
+                                            readopts: &ReadOptions)
+                                            -> Vec<Result<Option<DBVector>, Error>> {
+        if readopts.inner.is_null() {
+            return keys.iter().map(|_| Err(null_readopts_error())).collect();
+        }
+        if let Err(e) = readopts.check_snapshot() {
+            return keys.iter().map(|_| Err(e.clone())).collect();
+        }
+        let cf_handle = match cf.handle() {
+            Ok(cf_handle) => cf_handle,
+            Err(e) => return keys.iter().map(|_| Err(e.clone())).collect(),
+        };
+
+        let column_families: Vec<*const ffi::rocksdb_column_family_handle_t> =
+            keys.iter().map(|_| cf_handle as *const _).collect();
+        let keys_list: Vec<*const c_char> =
+            keys.iter().map(|key| key.as_ref().as_ptr() as *const c_char).collect();
+        let keys_list_sizes: Vec<size_t> =
+            keys.iter().map(|key| key.as_ref().len() as size_t).collect();
+        let mut values_list: Vec<*mut c_char> = vec![ptr::null_mut(); keys.len()];
+        let mut values_list_sizes: Vec<size_t> = vec![0; keys.len()];
+        let mut errs: Vec<*mut ffi::rocksdb_error_t> = vec![ptr::null_mut(); keys.len()];
+        unsafe {
+            ffi::rocksdb_ext_multi_get_cf(self.inner,
+                                          readopts.inner,
+                                          column_families.as_ptr(),
+                                          keys.len() as size_t,
+                                          keys_list.as_ptr(),
+                                          keys_list_sizes.as_ptr(),
+                                          values_list.as_mut_ptr(),
+                                          values_list_sizes.as_mut_ptr(),
+                                          errs.as_mut_ptr());
+        }
+        multi_get_results(values_list, values_list_sizes, errs)
+    }
+
+    /// Returns the values associated with the given keys within a column family, see
+    /// `DB::multi_get_cf_opt`.
+    pub fn multi_get_cf<K: AsRef<[u8]>>(&self,
+                                        cf: ColumnFamily,
+                                        keys: &[K])
+                                        -> Vec<Result<Option<DBVector>, Error>> {
+        self.multi_get_cf_opt(cf, keys, &ReadOptions::default())
+    }
+
+    pub fn create_cf<'a>(&'a self, name: &str, opts: &Options) -> Result<ColumnFamily<'a>, Error> {
         let cname = match CString::new(name.as_bytes()) {
             Ok(c) => c,
             Err(_) => {
-                return Err(Error::new("Failed to convert path to CString \
-                                       when opening rocksdb"
-                    .to_owned()))
+                return Err(Error::with_kind(ErrorKind::CStringConversion,
+                                            "Failed to convert path to CString \
+                                             when opening rocksdb"
+                                                .to_owned()))
             }
         };
-        let cf = unsafe {
+        try!(check_rtree_setup(opts));
+        let mut cfs = self.cfs.write().unwrap();
+        let state = unsafe {
             let cf_handler =
-                ffi_try!(ffi::rocksdb_create_column_family(self.inner, opts.inner, cname.as_ptr()));
-            let cf = ColumnFamily { inner: cf_handler };
-            self.cfs.insert(name.to_string(), cf);
-            cf
+                ffi_try!(ffi::rocksdb_ext_create_column_family(self.inner,
+                                                               opts.inner,
+                                                               cname.as_ptr()));
+            ColumnFamilyState::new(name, cf_handler, opts)
         };
+        let cf = ColumnFamily { state: unsafe { self.extend_cf_state(&state) } };
+        cfs.insert(name.to_string(), state);
         Ok(cf)
     }
 
-    pub fn drop_cf(&mut self, name: &str) -> Result<(), Error> {
-        let cf = self.cfs.get(name);
-        if cf.is_none() {
-            return Err(Error::new(format!("Invalid column family: {}", name).to_owned()));
-        }
+    /// Creates a column family that is indexed by an R-tree, see `Options::for_rtree`.
+    ///
+    /// It needs to be opened with `Options::for_rtree` and the same number of dimensions
+    /// later on, opening it with a different number returns an error. Keys with a different
+    /// number of dimensions or another coordinate type are rejected as well.
+    pub fn create_rtree_cf<'a>(&'a self,
+                               name: &str,
+                               dimensions: usize)
+                               -> Result<ColumnFamily<'a>, Error> {
+        self.create_cf(name, &Options::for_rtree(dimensions))
+    }
+
+    /// Like `DB::create_rtree_cf`, but for keys with the given coordinate type, see
+    /// `Options::for_rtree_with_type`.
+    pub fn create_rtree_cf_with_type<'a>(&'a self,
+                                         name: &str,
+                                         dimensions: usize,
+                                         coordinate_type: CoordinateType)
+                                         -> Result<ColumnFamily<'a>, Error> {
+        self.create_cf(name, &Options::for_rtree_with_type(dimensions, coordinate_type))
+    }
+
+    /// Drops a column family.
+    ///
+    /// Existing `ColumnFamily` instances of it return an error when they are used afterwards.
+    pub fn drop_cf(&self, name: &str) -> Result<(), Error> {
+        let mut cfs = self.cfs.write().unwrap();
+        let inner = match cfs.get(name) {
+            Some(state) => state.inner,
+            None => {
+                return Err(Error::with_kind(ErrorKind::InvalidArgument,
+                                            format!("Invalid column family: {}", name)))
+            }
+        };
         unsafe {
-            ffi_try!(ffi::rocksdb_drop_column_family(self.inner, cf.unwrap().inner));
+            ffi_try!(ffi::rocksdb_ext_drop_column_family(self.inner, inner));
         }
+        let state = cfs.remove(name).unwrap();
+        state.dropped.store(true, AtomicOrdering::SeqCst);
+        self.dropped_cfs.lock().unwrap().push(state);
         Ok(())
     }
 
     /// Return the underlying column family handle.
-    pub fn cf_handle(&self, name: &str) -> Option<ColumnFamily> {
-        self.cfs.get(name).cloned()
+    pub fn cf_handle<'a>(&'a self, name: &str) -> Option<ColumnFamily<'a>> {
+        self.cfs
+            .read()
+            .unwrap()
+            .get(name)
+            .map(|state| ColumnFamily { state: unsafe { self.extend_cf_state(state) } })
     }
 
-    pub fn iterator(&self, mode: IteratorMode) -> DBIterator {
-        let opts = ReadOptions::default();
-        DBIterator::new(self, &opts, mode)
+    /// Returns the state of a column family with the lifetime of the database.
+    ///
+    /// This is safe as the boxed states are only freed when the database is dropped, also
+    /// the ones of dropped column families.
+    unsafe fn extend_cf_state<'a>(&'a self,
+                                  state: &ColumnFamilyState)
+                                  -> &'a ColumnFamilyState {
+        &*(state as *const ColumnFamilyState)
     }
 
-    pub fn iterator_cf(&self,
-                       cf_handle: ColumnFamily,
-                       mode: IteratorMode)
-                       -> Result<DBIterator, Error> {
+    pub fn iterator<'a>(&'a self, mode: IteratorMode) -> DBIterator<'a> {
         let opts = ReadOptions::default();
-        DBIterator::new_cf(self, cf_handle, &opts, mode)
+        DBIterator::new(self, opts, mode)
     }
 
-    pub fn raw_iterator(&self) -> DBRawIterator {
+    pub fn iterator_cf<'a>(&'a self,
+                           cf_handle: ColumnFamily,
+                           mode: IteratorMode)
+                           -> Result<DBIterator<'a>, Error> {
         let opts = ReadOptions::default();
-        DBRawIterator::new(self, &opts)
+        DBIterator::new_cf(self, cf_handle, opts, mode)
     }
 
-    pub fn raw_iterator_cf(&self,
-                       cf_handle: ColumnFamily)
-                       -> Result<DBRawIterator, Error> {
+    pub fn raw_iterator<'a>(&'a self) -> DBRawIterator<'a> {
         let opts = ReadOptions::default();
-        DBRawIterator::new_cf(self, cf_handle, &opts)
+        DBRawIterator::new(self, opts)
     }
 
-    pub fn rtree_iterator(&self, mbb: &[u8]) -> DBIterator {
-        let mut opts = ReadOptions::default();
-        let context = RtreeIteratorContext::new(&mbb);
-        opts.set_iterator_context(&context);
+    pub fn raw_iterator_cf<'a>(&'a self,
+                               cf_handle: ColumnFamily)
+                               -> Result<DBRawIterator<'a>, Error> {
+        let opts = ReadOptions::default();
+        DBRawIterator::new_cf(self, cf_handle, opts)
+    }
 
+    /// Returns an iterator over the entries of the column family named `rtree` that intersect
+    /// with the given query.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if there is no column family named `rtree`, or if the query doesn't match its
+    ///   number of dimensions or coordinate type.
+    pub fn rtree_iterator<'a, Q: ToMbb + ?Sized>(&'a self, mbb: &Q) -> DBIterator<'a> {
         let rtree_cf = self.cf_handle("rtree").unwrap();
-        DBIterator::new_cf(self, rtree_cf, &opts, IteratorMode::Start).unwrap()
-
+        self.rtree_iterator_cf(rtree_cf, mbb).unwrap()
     }
 
-    pub fn snapshot(&self) -> Snapshot {
+    /// Returns an iterator over the entries of an R-tree column family that intersect with
+    /// the given query.
+    ///
+    /// Returns an error if the query has a different number of dimensions or another
+    /// coordinate type than the column family.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{DB, Options};
+    /// use rocksdb::rtree::{RtreeKey, RtreeQuery};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "path/for/rocksdb/storage10").unwrap();
+    /// let places = db.create_rtree_cf("places", 2).unwrap();
+    /// let key = RtreeKey::new("keypath", 1, vec![10.75, 11.11, 48.24, 48.50]);
+    /// db.put_cf(places, &key.to_bytes(), b"augsburg").unwrap();
+    ///
+    /// let query = RtreeQuery::new("keypath", (0, 10), vec![10.0, 11.0, 48.0, 49.0]);
+    /// for (key, value) in db.rtree_iterator_cf(places, &query).unwrap() {
+    ///     println!("Saw {:?} {:?}", key, value);
+    /// }
+    /// ```
+    pub fn rtree_iterator_cf<'a, Q: ToMbb + ?Sized>(&'a self,
+                                                    cf_handle: ColumnFamily,
+                                                    mbb: &Q)
+                                                    -> Result<DBIterator<'a>, Error> {
+        self.rtree_iterator_cf_opt(cf_handle, mbb, ReadOptions::default())
+    }
+
+    /// Like `DB::rtree_iterator_cf`, but with additional read options, e.g. an upper bound.
+    pub fn rtree_iterator_cf_opt<'a, Q: ToMbb + ?Sized>(&'a self,
+                                                        cf_handle: ColumnFamily,
+                                                        mbb: &Q,
+                                                        mut readopts: ReadOptions)
+                                                        -> Result<DBIterator<'a>, Error> {
+        try!(cf_handle.check_rtree_query(mbb));
+        try!(readopts.check_snapshot());
+        readopts.set_rtree_query(mbb);
+        DBIterator::new_cf(self, cf_handle, readopts, IteratorMode::Start)
+    }
+
+    pub fn snapshot<'a>(&'a self) -> Snapshot<'a> {
         Snapshot::new(self)
     }
 
     pub fn put_opt(&self, key: &[u8], value: &[u8], writeopts: &WriteOptions) -> Result<(), Error> {
         unsafe {
-            ffi_try!(ffi::rocksdb_put(self.inner,
-                                      writeopts.inner,
-                                      key.as_ptr() as *const c_char,
-                                      key.len() as size_t,
-                                      value.as_ptr() as *const c_char,
-                                      value.len() as size_t));
+            ffi_try!(ffi::rocksdb_ext_put(self.inner,
+                                          writeopts.inner,
+                                          key.as_ptr() as *const c_char,
+                                          key.len() as size_t,
+                                          value.as_ptr() as *const c_char,
+                                          value.len() as size_t));
             Ok(())
         }
     }
@@ -906,14 +1279,15 @@ impl DB {
                       value: &[u8],
                       writeopts: &WriteOptions)
                       -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
         unsafe {
-            ffi_try!(ffi::rocksdb_put_cf(self.inner,
-                                         writeopts.inner,
-                                         cf.inner,
-                                         key.as_ptr() as *const c_char,
-                                         key.len() as size_t,
-                                         value.as_ptr() as *const c_char,
-                                         value.len() as size_t));
+            ffi_try!(ffi::rocksdb_ext_put_cf(self.inner,
+                                             writeopts.inner,
+                                             try!(cf.handle()),
+                                             key.as_ptr() as *const c_char,
+                                             key.len() as size_t,
+                                             value.as_ptr() as *const c_char,
+                                             value.len() as size_t));
             Ok(())
         }
     }
@@ -924,12 +1298,12 @@ impl DB {
                      writeopts: &WriteOptions)
                      -> Result<(), Error> {
         unsafe {
-            ffi_try!(ffi::rocksdb_merge(self.inner,
-                                        writeopts.inner,
-                                        key.as_ptr() as *const c_char,
-                                        key.len() as size_t,
-                                        value.as_ptr() as *const c_char,
-                                        value.len() as size_t));
+            ffi_try!(ffi::rocksdb_ext_merge(self.inner,
+                                            writeopts.inner,
+                                            key.as_ptr() as *const c_char,
+                                            key.len() as size_t,
+                                            value.as_ptr() as *const c_char,
+                                            value.len() as size_t));
             Ok(())
         }
     }
@@ -940,24 +1314,25 @@ impl DB {
                         value: &[u8],
                         writeopts: &WriteOptions)
                         -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
         unsafe {
-            ffi_try!(ffi::rocksdb_merge_cf(self.inner,
-                                           writeopts.inner,
-                                           cf.inner,
-                                           key.as_ptr() as *const c_char,
-                                           key.len() as size_t,
-                                           value.as_ptr() as *const c_char,
-                                           value.len() as size_t));
+            ffi_try!(ffi::rocksdb_ext_merge_cf(self.inner,
+                                               writeopts.inner,
+                                               try!(cf.handle()),
+                                               key.as_ptr() as *const c_char,
+                                               key.len() as size_t,
+                                               value.as_ptr() as *const c_char,
+                                               value.len() as size_t));
             Ok(())
         }
     }
 
     pub fn delete_opt(&self, key: &[u8], writeopts: &WriteOptions) -> Result<(), Error> {
         unsafe {
-            ffi_try!(ffi::rocksdb_delete(self.inner,
-                                         writeopts.inner,
-                                         key.as_ptr() as *const c_char,
-                                         key.len() as size_t));
+            ffi_try!(ffi::rocksdb_ext_delete(self.inner,
+                                             writeopts.inner,
+                                             key.as_ptr() as *const c_char,
+                                             key.len() as size_t));
             Ok(())
         }
     }
@@ -967,12 +1342,71 @@ impl DB {
                          key: &[u8],
                          writeopts: &WriteOptions)
                          -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
         unsafe {
-            ffi_try!(ffi::rocksdb_delete_cf(self.inner,
-                                            writeopts.inner,
-                                            cf.inner,
-                                            key.as_ptr() as *const c_char,
-                                            key.len() as size_t));
+            ffi_try!(ffi::rocksdb_ext_delete_cf(self.inner,
+                                                writeopts.inner,
+                                                try!(cf.handle()),
+                                                key.as_ptr() as *const c_char,
+                                                key.len() as size_t));
+            Ok(())
+        }
+    }
+
+    /// Removes the database entry for a key that was written exactly once.
+    ///
+    /// Unlike the tombstone of a normal delete, it is dropped together with the value during
+    /// compaction. The result is undefined if the key was written more than once or mixed with
+    /// `delete` or `merge`, e.g. an older value might show up again after a flush. It's up to
+    /// the caller to uphold this, it isn't checked.
+    pub fn single_delete_opt(&self, key: &[u8], writeopts: &WriteOptions) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_singledelete(self.inner,
+                                               writeopts.inner,
+                                               key.as_ptr() as *const c_char,
+                                               key.len() as size_t));
+        }
+        Ok(())
+    }
+
+    /// Removes the database entry of a column family for a key that was written exactly once,
+    /// see `DB::single_delete_opt`.
+    pub fn single_delete_cf_opt(&self,
+                                cf: ColumnFamily,
+                                key: &[u8],
+                                writeopts: &WriteOptions)
+                                -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi_try!(ffi::rocksdb_singledelete_cf(self.inner,
+                                                  writeopts.inner,
+                                                  try!(cf.handle()),
+                                                  key.as_ptr() as *const c_char,
+                                                  key.len() as size_t));
+        }
+        Ok(())
+    }
+
+    /// Removes the database entries of a column family in the range `[start, end)`.
+    ///
+    /// A single range tombstone is written, which is much cheaper than deleting the keys one
+    /// by one. Iterators and snapshots that are created afterwards don't see the keys.
+    pub fn delete_range_cf_opt(&self,
+                               cf: ColumnFamily,
+                               start: &[u8],
+                               end: &[u8],
+                               writeopts: &WriteOptions)
+                               -> Result<(), Error> {
+        try!(cf.check_rtree_key(start));
+        try!(cf.check_rtree_key(end));
+        unsafe {
+            ffi_try!(ffi::rocksdb_delete_range_cf(self.inner,
+                                                  writeopts.inner,
+                                                  try!(cf.handle()),
+                                                  start.as_ptr() as *const c_char,
+                                                  start.len() as size_t,
+                                                  end.as_ptr() as *const c_char,
+                                                  end.len() as size_t));
             Ok(())
         }
     }
@@ -1012,6 +1446,189 @@ impl DB {
         self.delete_cf_opt(cf, key, &WriteOptions::default())
     }
 
+    pub fn single_delete(&self, key: &[u8]) -> Result<(), Error> {
+        self.single_delete_opt(key, &WriteOptions::default())
+    }
+
+    pub fn single_delete_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<(), Error> {
+        self.single_delete_cf_opt(cf, key, &WriteOptions::default())
+    }
+
+    /// Removes the database entries of a column family in the range `[start, end)`, see
+    /// `DB::delete_range_cf_opt`.
+    pub fn delete_range_cf(&self,
+                           cf: ColumnFamily,
+                           start: &[u8],
+                           end: &[u8])
+                           -> Result<(), Error> {
+        self.delete_range_cf_opt(cf, start, end, &WriteOptions::default())
+    }
+
+    /// Deletes the SST files that only contain keys within `[start, end]`, in all levels
+    /// but level 0.
+    ///
+    /// This is much cheaper than deleting the keys, though keys within the range that are
+    /// stored in other files stay. Snapshots that were taken before might no longer see the
+    /// deleted data.
+    pub fn delete_file_in_range(&self, start: &[u8], end: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_ext_delete_file_in_range(self.inner,
+                                                           start.as_ptr() as *const c_char,
+                                                           start.len() as size_t,
+                                                           end.as_ptr() as *const c_char,
+                                                           end.len() as size_t));
+        }
+        Ok(())
+    }
+
+    /// Deletes the SST files of a column family that only contain keys within
+    /// `[start, end]`, see `DB::delete_file_in_range`.
+    pub fn delete_file_in_range_cf(&self,
+                                   cf: ColumnFamily,
+                                   start: &[u8],
+                                   end: &[u8])
+                                   -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_ext_delete_file_in_range_cf(self.inner,
+                                                              try!(cf.handle()),
+                                                              start.as_ptr() as *const c_char,
+                                                              start.len() as size_t,
+                                                              end.as_ptr() as *const c_char,
+                                                              end.len() as size_t));
+        }
+        Ok(())
+    }
+
+    /// Deletes all keys within `[start, end)`.
+    ///
+    /// First the SST files that are fully within the range are deleted with
+    /// `DB::delete_file_in_range`, then the remaining keys at the edges of the range are
+    /// deleted with a range tombstone and compacted away. Snapshots that were taken before
+    /// might no longer see the deleted data.
+    pub fn drop_range(&self, start: &[u8], end: &[u8]) -> Result<(), Error> {
+        self.drop_range_opt(None, start, end)
+    }
+
+    /// Deletes all keys of a column family within `[start, end)`, see `DB::drop_range`.
+    pub fn drop_range_cf(&self, cf: ColumnFamily, start: &[u8], end: &[u8]) -> Result<(), Error> {
+        self.drop_range_opt(Some(cf), start, end)
+    }
+
+    fn drop_range_opt(&self,
+                      cf: Option<ColumnFamily>,
+                      start: &[u8],
+                      end: &[u8])
+                      -> Result<(), Error> {
+        let new_iterator = || {
+            let mut readopts = ReadOptions::default();
+            readopts.set_iterate_upper_bound(end);
+            match cf {
+                Some(cf) => DBRawIterator::new_cf(self, cf, readopts),
+                None => Ok(DBRawIterator::new(self, readopts)),
+            }
+        };
+
+        // The end of `delete_file_in_range` is inclusive, hence the last key before `end` is
+        // used as limit. Files that end between that key and `end` can only contain
+        // deletions, so it's fine to keep them.
+        let last_key = {
+            let mut iter = try!(new_iterator());
+            iter.seek(start);
+            if !iter.valid() {
+                return Ok(());
+            }
+            iter.seek_for_prev(end);
+            if iter.key().map_or(false, |key| key == end) {
+                iter.prev();
+            }
+            iter.key()
+        };
+        if let Some(last_key) = last_key {
+            try!(match cf {
+                Some(cf) => self.delete_file_in_range_cf(cf, start, &last_key),
+                None => self.delete_file_in_range(start, &last_key),
+            });
+        }
+
+        let mut batch = WriteBatch::default();
+        try!(match cf {
+            Some(cf) => batch.delete_range_cf(cf, start, end),
+            None => batch.delete_range(start, end),
+        });
+        try!(self.write(batch));
+
+        match cf {
+            Some(cf) => self.compact_range_cf(cf, Some(start), Some(end)),
+            None => self.compact_range(Some(start), Some(end)),
+        }
+        Ok(())
+    }
+
+    /// Returns the metadata of all SST files that are currently in use, of all column
+    /// families.
+    pub fn live_files(&self) -> Result<Vec<LiveFile>, Error> {
+        unsafe {
+            let files = ffi::rocksdb_livefiles(self.inner);
+            if files.is_null() {
+                return Err(Error::with_kind(ErrorKind::NullHandle,
+                                            "Could not get the live files.".to_owned()));
+            }
+
+            let count = ffi::rocksdb_livefiles_count(files);
+            let mut live_files = Vec::with_capacity(count as usize);
+            for index in 0..count {
+                let mut smallest_key_len: size_t = 0;
+                let smallest_key = ffi::rocksdb_livefiles_smallestkey(files,
+                                                                      index,
+                                                                      &mut smallest_key_len);
+                let mut largest_key_len: size_t = 0;
+                let largest_key = ffi::rocksdb_livefiles_largestkey(files,
+                                                                    index,
+                                                                    &mut largest_key_len);
+                let column_family_name = ffi::rocksdb_livefiles_column_family_name(files, index);
+                live_files.push(LiveFile {
+                    column_family_name: c_str_to_string(column_family_name),
+                    name: c_str_to_string(ffi::rocksdb_livefiles_name(files, index)),
+                    size: ffi::rocksdb_livefiles_size(files, index),
+                    level: ffi::rocksdb_livefiles_level(files, index),
+                    smallest_key: slice::from_raw_parts(smallest_key as *const u8,
+                                                        smallest_key_len)
+                        .to_vec(),
+                    largest_key: slice::from_raw_parts(largest_key as *const u8, largest_key_len)
+                        .to_vec(),
+                });
+            }
+            ffi::rocksdb_livefiles_destroy(files);
+            Ok(live_files)
+        }
+    }
+
+    /// Flushes the memtables of the default column family to disk.
+    pub fn flush_opt(&self, flushopts: &FlushOptions) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_ext_flush(self.inner, flushopts.inner));
+        }
+        Ok(())
+    }
+
+    /// Flushes the memtables of the default column family to disk and waits until it's done.
+    pub fn flush(&self) -> Result<(), Error> {
+        self.flush_opt(&FlushOptions::default())
+    }
+
+    /// Flushes the memtables of a column family to disk.
+    pub fn flush_cf_opt(&self, cf: ColumnFamily, flushopts: &FlushOptions) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_flush_cf(self.inner, flushopts.inner, try!(cf.handle())));
+        }
+        Ok(())
+    }
+
+    /// Flushes the memtables of a column family to disk and waits until it's done.
+    pub fn flush_cf(&self, cf: ColumnFamily) -> Result<(), Error> {
+        self.flush_cf_opt(cf, &FlushOptions::default())
+    }
+
     pub fn compact_range(&self, start: Option<&[u8]>, end: Option<&[u8]>) {
         unsafe {
             ffi::rocksdb_compact_range(self.inner,
@@ -1027,45 +1644,304 @@ impl DB {
                             start: Option<&[u8]>,
                             end: Option<&[u8]>) {
         unsafe {
+            // The handle stays valid even if the column family was dropped
             ffi::rocksdb_compact_range_cf(self.inner,
-                                          cf.inner,
+                                          cf.state.inner,
                                           opt_bytes_to_ptr(start),
                                           start.map_or(0, |s| s.len()) as size_t,
                                           opt_bytes_to_ptr(end),
                                           end.map_or(0, |e| e.len()) as size_t);
         }
     }
-}
 
-impl WriteBatch {
-    pub fn len(&self) -> usize {
-        unsafe { ffi::rocksdb_writebatch_count(self.inner) as usize }
+    /// Returns the current statistics, `None` if they weren't enabled with
+    /// `Options::enable_statistics` when the database was opened.
+    pub fn statistics(&self) -> Result<Option<Statistics>, Error> {
+        if self.statistics.is_null() {
+            return Ok(None);
+        }
+        let stats = unsafe {
+            let value = ffi::rocksdb_statistics_to_string(self.statistics);
+            let stats = CStr::from_ptr(value).to_string_lossy().into_owned();
+            libc::free(value as *mut c_void);
+            stats
+        };
+        Statistics::parse(&stats).map(Some)
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    /// Returns the value of a database property, `None` if the property isn't known.
+    ///
+    /// The name is either a `properties::Property` or a plain string.
+    pub fn property_value<P: PropertyName + ?Sized>(&self,
+                                                    name: &P)
+                                                    -> Result<Option<String>, Error> {
+        let cname = try!(property_name_to_cstring(name));
+        unsafe {
+            Ok(property_value_from_c(ffi::rocksdb_property_value(self.inner, cname.as_ptr())))
+        }
     }
 
-    /// Insert a value into the database under the given key.
-    pub fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+    /// Returns the value of a database property within a column family, `None` if the
+    /// property isn't known.
+    pub fn property_value_cf<P: PropertyName + ?Sized>(&self,
+                                                       cf: ColumnFamily,
+                                                       name: &P)
+                                                       -> Result<Option<String>, Error> {
+        let cname = try!(property_name_to_cstring(name));
         unsafe {
-            ffi::rocksdb_writebatch_put(self.inner,
-                                        key.as_ptr() as *const c_char,
-                                        key.len() as size_t,
-                                        value.as_ptr() as *const c_char,
-                                        value.len() as size_t);
-            Ok(())
+            Ok(property_value_from_c(ffi::rocksdb_property_value_cf(self.inner,
+                                                                    try!(cf.handle()),
+                                                                    cname.as_ptr())))
         }
     }
 
-    pub fn put_cf(&mut self,
-                  cf: ColumnFamily,
-                  key: &[u8],
-                  value: &[u8])
-                  -> Result<(), Error> {
+    /// Returns the value of a database property that is an integer, `None` if the property
+    /// isn't known.
+    ///
+    /// Returns an error if the property isn't an integer, e.g. `Property::Stats`.
+    pub fn property_int_value<P: PropertyName + ?Sized>(&self,
+                                                        name: &P)
+                                                        -> Result<Option<u64>, Error> {
+        match try!(self.property_value(name)) {
+            Some(value) => parse_property_int(name, &value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the value of a database property within a column family that is an integer,
+    /// `None` if the property isn't known.
+    pub fn property_int_value_cf<P: PropertyName + ?Sized>(&self,
+                                                           cf: ColumnFamily,
+                                                           name: &P)
+                                                           -> Result<Option<u64>, Error> {
+        match try!(self.property_value_cf(cf, name)) {
+            Some(value) => parse_property_int(name, &value).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+fn property_name_to_cstring<P: PropertyName + ?Sized>(name: &P) -> Result<CString, Error> {
+    match CString::new(name.property_name().as_bytes()) {
+        Ok(cname) => Ok(cname),
+        Err(_) => {
+            Err(Error::with_kind(ErrorKind::CStringConversion,
+                                 "Failed to convert property name to CString".to_owned()))
+        }
+    }
+}
+
+unsafe fn property_value_from_c(value: *mut c_char) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    let s = String::from_utf8_lossy(CStr::from_ptr(value).to_bytes()).into_owned();
+    libc::free(value as *mut c_void);
+    Some(s)
+}
+
+fn parse_property_int<P: PropertyName + ?Sized>(name: &P, value: &str) -> Result<u64, Error> {
+    value.trim().parse::<u64>().map_err(|_| {
+        Error::with_kind(ErrorKind::InvalidArgument,
+                         format!("Property {} is not an integer: {}",
+                                 name.property_name(),
+                                 value))
+    })
+}
+
+impl ReadOnlyDB {
+    /// Open a database read-only with the specified options.
+    ///
+    /// If `error_if_log_file_exists` is set, opening fails if there is a write ahead log that
+    /// wasn't flushed yet, e.g. as the database is still opened by a writer.
+    pub fn open<P: AsRef<Path>>(opts: &Options,
+                                path: P,
+                                error_if_log_file_exists: bool)
+                                -> Result<ReadOnlyDB, Error> {
+        ReadOnlyDB::open_cf(opts, path, &[], &[], error_if_log_file_exists)
+    }
+
+    /// Open a database read-only with specified database options and column family options,
+    /// see `DB::open_cf`.
+    pub fn open_cf<P: AsRef<Path>>(opts: &Options,
+                                   path: P,
+                                   cfs: &[&str],
+                                   cf_opts: &[&Options],
+                                   error_if_log_file_exists: bool)
+                                   -> Result<ReadOnlyDB, Error> {
+        let mode = OpenMode::ReadOnly { error_if_log_file_exists: error_if_log_file_exists };
+        let db = try!(DB::open_cf_with_mode(opts, path, cfs, cf_opts, mode));
+        Ok(ReadOnlyDB { db: db })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.db.path()
+    }
+
+    pub fn get_opt(&self, key: &[u8], readopts: &ReadOptions) -> Result<Option<DBVector>, Error> {
+        self.db.get_opt(key, readopts)
+    }
+
+    pub fn get(&self, key: &[u8]) -> Result<Option<DBVector>, Error> {
+        self.db.get(key)
+    }
+
+    pub fn get_cf_opt(&self,
+                      cf: ColumnFamily,
+                      key: &[u8],
+                      readopts: &ReadOptions)
+                      -> Result<Option<DBVector>, Error> {
+        self.db.get_cf_opt(cf, key, readopts)
+    }
+
+    pub fn get_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<Option<DBVector>, Error> {
+        self.db.get_cf(cf, key)
+    }
+
+    pub fn multi_get_opt<K: AsRef<[u8]>>(&self,
+                                         keys: &[K],
+                                         readopts: &ReadOptions)
+                                         -> Vec<Result<Option<DBVector>, Error>> {
+        self.db.multi_get_opt(keys, readopts)
+    }
+
+    pub fn multi_get<K: AsRef<[u8]>>(&self, keys: &[K]) -> Vec<Result<Option<DBVector>, Error>> {
+        self.db.multi_get(keys)
+    }
+
+    pub fn multi_get_cf_opt<K: AsRef<[u8]>>(&self,
+                                            cf: ColumnFamily,
+                                            keys: &[K],
+                                            readopts: &ReadOptions)
+                                            -> Vec<Result<Option<DBVector>, Error>> {
+        self.db.multi_get_cf_opt(cf, keys, readopts)
+    }
+
+    pub fn multi_get_cf<K: AsRef<[u8]>>(&self,
+                                        cf: ColumnFamily,
+                                        keys: &[K])
+                                        -> Vec<Result<Option<DBVector>, Error>> {
+        self.db.multi_get_cf(cf, keys)
+    }
+
+    pub fn cf_handle<'a>(&'a self, name: &str) -> Option<ColumnFamily<'a>> {
+        self.db.cf_handle(name)
+    }
+
+    pub fn iterator<'a>(&'a self, mode: IteratorMode) -> DBIterator<'a> {
+        self.db.iterator(mode)
+    }
+
+    pub fn iterator_cf<'a>(&'a self,
+                           cf_handle: ColumnFamily,
+                           mode: IteratorMode)
+                           -> Result<DBIterator<'a>, Error> {
+        self.db.iterator_cf(cf_handle, mode)
+    }
+
+    pub fn raw_iterator<'a>(&'a self) -> DBRawIterator<'a> {
+        self.db.raw_iterator()
+    }
+
+    pub fn raw_iterator_cf<'a>(&'a self,
+                               cf_handle: ColumnFamily)
+                               -> Result<DBRawIterator<'a>, Error> {
+        self.db.raw_iterator_cf(cf_handle)
+    }
+
+    /// See `DB::rtree_iterator`.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if there is no column family named `rtree`.
+    pub fn rtree_iterator<'a, Q: ToMbb + ?Sized>(&'a self, mbb: &Q) -> DBIterator<'a> {
+        self.db.rtree_iterator(mbb)
+    }
+
+    pub fn rtree_iterator_cf<'a, Q: ToMbb + ?Sized>(&'a self,
+                                                    cf_handle: ColumnFamily,
+                                                    mbb: &Q)
+                                                    -> Result<DBIterator<'a>, Error> {
+        self.db.rtree_iterator_cf(cf_handle, mbb)
+    }
+
+    pub fn rtree_iterator_cf_opt<'a, Q: ToMbb + ?Sized>(&'a self,
+                                                        cf_handle: ColumnFamily,
+                                                        mbb: &Q,
+                                                        readopts: ReadOptions)
+                                                        -> Result<DBIterator<'a>, Error> {
+        self.db.rtree_iterator_cf_opt(cf_handle, mbb, readopts)
+    }
+
+    pub fn snapshot<'a>(&'a self) -> Snapshot<'a> {
+        self.db.snapshot()
+    }
+
+    pub fn live_files(&self) -> Result<Vec<LiveFile>, Error> {
+        self.db.live_files()
+    }
+
+    pub fn statistics(&self) -> Result<Option<Statistics>, Error> {
+        self.db.statistics()
+    }
+
+    pub fn property_value<P: PropertyName + ?Sized>(&self,
+                                                    name: &P)
+                                                    -> Result<Option<String>, Error> {
+        self.db.property_value(name)
+    }
+
+    pub fn property_value_cf<P: PropertyName + ?Sized>(&self,
+                                                       cf: ColumnFamily,
+                                                       name: &P)
+                                                       -> Result<Option<String>, Error> {
+        self.db.property_value_cf(cf, name)
+    }
+
+    pub fn property_int_value<P: PropertyName + ?Sized>(&self,
+                                                        name: &P)
+                                                        -> Result<Option<u64>, Error> {
+        self.db.property_int_value(name)
+    }
+
+    pub fn property_int_value_cf<P: PropertyName + ?Sized>(&self,
+                                                           cf: ColumnFamily,
+                                                           name: &P)
+                                                           -> Result<Option<u64>, Error> {
+        self.db.property_int_value_cf(cf, name)
+    }
+}
+
+impl WriteBatch {
+    pub fn len(&self) -> usize {
+        unsafe { ffi::rocksdb_writebatch_count(self.inner) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert a value into the database under the given key.
+    pub fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi::rocksdb_writebatch_put(self.inner,
+                                        key.as_ptr() as *const c_char,
+                                        key.len() as size_t,
+                                        value.as_ptr() as *const c_char,
+                                        value.len() as size_t);
+            Ok(())
+        }
+    }
+
+    pub fn put_cf(&mut self,
+                  cf: ColumnFamily,
+                  key: &[u8],
+                  value: &[u8])
+                  -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
         unsafe {
             ffi::rocksdb_writebatch_put_cf(self.inner,
-                                           cf.inner,
+                                           try!(cf.handle()),
                                            key.as_ptr() as *const c_char,
                                            key.len() as size_t,
                                            value.as_ptr() as *const c_char,
@@ -1090,9 +1966,10 @@ impl WriteBatch {
                     key: &[u8],
                     value: &[u8])
                     -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
         unsafe {
             ffi::rocksdb_writebatch_merge_cf(self.inner,
-                                             cf.inner,
+                                             try!(cf.handle()),
                                              key.as_ptr() as *const c_char,
                                              key.len() as size_t,
                                              value.as_ptr() as *const c_char,
@@ -1117,14 +1994,68 @@ impl WriteBatch {
                      cf: ColumnFamily,
                      key: &[u8])
                      -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
         unsafe {
             ffi::rocksdb_writebatch_delete_cf(self.inner,
-                                              cf.inner,
+                                              try!(cf.handle()),
                                               key.as_ptr() as *const c_char,
                                               key.len() as size_t);
             Ok(())
         }
     }
+
+    /// Remove the database entry for a key that was written exactly once, see
+    /// `DB::single_delete_opt`.
+    pub fn single_delete(&mut self, key: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete(self.inner,
+                                                 key.as_ptr() as *const c_char,
+                                                 key.len() as size_t);
+            Ok(())
+        }
+    }
+
+    pub fn single_delete_cf(&mut self, cf: ColumnFamily, key: &[u8]) -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete_cf(self.inner,
+                                                    try!(cf.handle()),
+                                                    key.as_ptr() as *const c_char,
+                                                    key.len() as size_t);
+            Ok(())
+        }
+    }
+
+    /// Remove the database entries in the range `[start, end)` with a single range
+    /// tombstone.
+    pub fn delete_range(&mut self, start: &[u8], end: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi::rocksdb_writebatch_delete_range(self.inner,
+                                                 start.as_ptr() as *const c_char,
+                                                 start.len() as size_t,
+                                                 end.as_ptr() as *const c_char,
+                                                 end.len() as size_t);
+            Ok(())
+        }
+    }
+
+    pub fn delete_range_cf(&mut self,
+                           cf: ColumnFamily,
+                           start: &[u8],
+                           end: &[u8])
+                           -> Result<(), Error> {
+        try!(cf.check_rtree_key(start));
+        try!(cf.check_rtree_key(end));
+        unsafe {
+            ffi::rocksdb_writebatch_delete_range_cf(self.inner,
+                                                    try!(cf.handle()),
+                                                    start.as_ptr() as *const c_char,
+                                                    start.len() as size_t,
+                                                    end.as_ptr() as *const c_char,
+                                                    end.len() as size_t);
+            Ok(())
+        }
+    }
 }
 
 impl Default for WriteBatch {
@@ -1139,17 +2070,378 @@ impl Drop for WriteBatch {
     }
 }
 
+impl WriteBatchLike for WriteBatch {
+    fn write_to(self, db: &DB, writeopts: &WriteOptions) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_ext_write(db.inner, writeopts.inner, self.inner));
+        }
+        Ok(())
+    }
+}
+
+impl WriteBatchWithIndex {
+    /// Creates a batch, `reserved_bytes` are allocated upfront.
+    ///
+    /// The keys written without a column family are ordered by the comparator of `opts`, which
+    /// needs to be the one of the default column family of the database the batch is read
+    /// together with. The keys of other column families are ordered by their own comparator.
+    ///
+    /// If `overwrite_key` is `true`, only the most recent write of a key is indexed. This is
+    /// required for reading back merges and for `iterator_with_base`.
+    pub fn new(opts: &Options, reserved_bytes: usize, overwrite_key: bool) -> WriteBatchWithIndex {
+        WriteBatchWithIndex {
+            inner: unsafe {
+                ffi::rocksdb_writebatch_wi_create(opts.inner,
+                                                  reserved_bytes as size_t,
+                                                  overwrite_key as c_uchar)
+            },
+            overwrite_key: overwrite_key,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { ffi::rocksdb_writebatch_wi_count(self.inner) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all writes from the batch.
+    pub fn clear(&mut self) {
+        unsafe {
+            ffi::rocksdb_writebatch_wi_clear(self.inner);
+        }
+    }
+
+    /// Insert a value into the database under the given key.
+    pub fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi::rocksdb_writebatch_wi_put(self.inner,
+                                           key.as_ptr() as *const c_char,
+                                           key.len() as size_t,
+                                           value.as_ptr() as *const c_char,
+                                           value.len() as size_t);
+            Ok(())
+        }
+    }
+
+    pub fn put_cf(&mut self,
+                  cf: ColumnFamily,
+                  key: &[u8],
+                  value: &[u8])
+                  -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi::rocksdb_writebatch_wi_put_cf(self.inner,
+                                              try!(cf.handle()),
+                                              key.as_ptr() as *const c_char,
+                                              key.len() as size_t,
+                                              value.as_ptr() as *const c_char,
+                                              value.len() as size_t);
+            Ok(())
+        }
+    }
+
+    pub fn merge(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi::rocksdb_writebatch_wi_merge(self.inner,
+                                             key.as_ptr() as *const c_char,
+                                             key.len() as size_t,
+                                             value.as_ptr() as *const c_char,
+                                             value.len() as size_t);
+            Ok(())
+        }
+    }
+
+    pub fn merge_cf(&mut self,
+                    cf: ColumnFamily,
+                    key: &[u8],
+                    value: &[u8])
+                    -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi::rocksdb_writebatch_wi_merge_cf(self.inner,
+                                                try!(cf.handle()),
+                                                key.as_ptr() as *const c_char,
+                                                key.len() as size_t,
+                                                value.as_ptr() as *const c_char,
+                                                value.len() as size_t);
+            Ok(())
+        }
+    }
+
+    /// Remove the database entry for key.
+    pub fn delete(&mut self, key: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi::rocksdb_writebatch_wi_delete(self.inner,
+                                              key.as_ptr() as *const c_char,
+                                              key.len() as size_t);
+            Ok(())
+        }
+    }
+
+    pub fn delete_cf(&mut self, cf: ColumnFamily, key: &[u8]) -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi::rocksdb_writebatch_wi_delete_cf(self.inner,
+                                                 try!(cf.handle()),
+                                                 key.as_ptr() as *const c_char,
+                                                 key.len() as size_t);
+            Ok(())
+        }
+    }
+
+    /// Returns the value the batch writes for a key, the database isn't read.
+    ///
+    /// Returns `None` if the batch doesn't write the key or deletes it. If the batch merges
+    /// into the key, an error of kind `ErrorKind::MergeInProgress` is returned.
+    pub fn get_from_batch(&self, key: &[u8]) -> Result<Option<DBVector>, Error> {
+        let opts = Options::default();
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch(self.inner,
+                                                                         opts.inner,
+                                                                         key.as_ptr() as
+                                                                         *const c_char,
+                                                                         key.len() as size_t,
+                                                                         &mut val_len)) as
+                      *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    pub fn get_from_batch_cf(&self,
+                             cf: ColumnFamily,
+                             key: &[u8])
+                             -> Result<Option<DBVector>, Error> {
+        let opts = Options::default();
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_cf(self.inner,
+                                                                            opts.inner,
+                                                                            try!(cf.handle()),
+                                                                            key.as_ptr() as
+                                                                            *const c_char,
+                                                                            key.len() as size_t,
+                                                                            &mut val_len)) as
+                      *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    /// Returns the value a key would have if the batch was written to the database now.
+    pub fn get_from_batch_and_db_opt(&self,
+                                     db: &DB,
+                                     key: &[u8],
+                                     readopts: &ReadOptions)
+                                     -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(null_readopts_error());
+        }
+        try!(readopts.check_snapshot());
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_and_db(self.inner,
+                                                                                db.inner,
+                                                                                readopts.inner,
+                                                                                key.as_ptr() as
+                                                                                *const c_char,
+                                                                                key.len() as
+                                                                                size_t,
+                                                                                &mut val_len)) as
+                      *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    pub fn get_from_batch_and_db(&self, db: &DB, key: &[u8]) -> Result<Option<DBVector>, Error> {
+        self.get_from_batch_and_db_opt(db, key, &ReadOptions::default())
+    }
+
+    pub fn get_from_batch_and_db_cf_opt(&self,
+                                        db: &DB,
+                                        cf: ColumnFamily,
+                                        key: &[u8],
+                                        readopts: &ReadOptions)
+                                        -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(null_readopts_error());
+        }
+        try!(readopts.check_snapshot());
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_and_db_cf(
+                self.inner,
+                db.inner,
+                readopts.inner,
+                try!(cf.handle()),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                &mut val_len)) as *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    pub fn get_from_batch_and_db_cf(&self,
+                                    db: &DB,
+                                    cf: ColumnFamily,
+                                    key: &[u8])
+                                    -> Result<Option<DBVector>, Error> {
+        self.get_from_batch_and_db_cf_opt(db, cf, key, &ReadOptions::default())
+    }
+
+    /// Returns an iterator over the given one of the default column family, with the writes
+    /// of the batch applied on top of it.
+    ///
+    /// Returns an error of kind `ErrorKind::InvalidArgument` if the batch wasn't created with
+    /// `overwrite_key` set to `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{DB, WriteBatchWithIndex};
+    ///
+    /// let db = DB::open_default("path/for/rocksdb/storage22").unwrap();
+    /// db.put(b"a", b"1").unwrap();
+    /// db.put(b"b", b"2").unwrap();
+    ///
+    /// let mut batch = WriteBatchWithIndex::default();
+    /// batch.delete(b"a").unwrap();
+    /// batch.put(b"c", b"3").unwrap();
+    ///
+    /// let mut iter = batch.iterator_with_base(db.raw_iterator()).unwrap();
+    /// iter.seek_to_first();
+    /// assert_eq!(iter.key(), Some(b"b".to_vec()));
+    /// iter.next();
+    /// assert_eq!(iter.key(), Some(b"c".to_vec()));
+    /// ```
+    pub fn iterator_with_base<'a>(&'a self,
+                                  base: DBRawIterator<'a>)
+                                  -> Result<DBRawIterator<'a>, Error> {
+        try!(self.check_overwrite_key());
+        let db = base.db;
+        unsafe {
+            let (base_inner, readopts) = base.into_parts();
+            let inner = ffi_try!(ffi::rocksdb_writebatch_wi_create_iterator_with_base(self.inner,
+                                                                                      db.inner,
+                                                                                      base_inner));
+            Ok(DBRawIterator {
+                inner: inner,
+                _readopts: readopts,
+                db: db,
+            })
+        }
+    }
+
+    /// Like `iterator_with_base`, for an iterator over the given column family.
+    pub fn iterator_with_base_cf<'a>(&'a self,
+                                     base: DBRawIterator<'a>,
+                                     cf: ColumnFamily)
+                                     -> Result<DBRawIterator<'a>, Error> {
+        try!(self.check_overwrite_key());
+        let cf_inner = try!(cf.handle());
+        let db = base.db;
+        unsafe {
+            let (base_inner, readopts) = base.into_parts();
+            let inner =
+                ffi_try!(ffi::rocksdb_writebatch_wi_create_iterator_with_base_cf(self.inner,
+                                                                                 base_inner,
+                                                                                 cf_inner));
+            Ok(DBRawIterator {
+                inner: inner,
+                _readopts: readopts,
+                db: db,
+            })
+        }
+    }
+
+    fn check_overwrite_key(&self) -> Result<(), Error> {
+        if self.overwrite_key {
+            Ok(())
+        } else {
+            Err(Error::with_kind(ErrorKind::InvalidArgument,
+                                 "Iterating with a base requires a batch that was created \
+                                  with `overwrite_key` set to `true`."
+                                     .to_owned()))
+        }
+    }
+}
+
+impl Default for WriteBatchWithIndex {
+    fn default() -> WriteBatchWithIndex {
+        WriteBatchWithIndex::new(&Options::default(), 0, true)
+    }
+}
+
+impl Drop for WriteBatchWithIndex {
+    fn drop(&mut self) {
+        unsafe { ffi::rocksdb_writebatch_wi_destroy(self.inner) }
+    }
+}
+
+impl WriteBatchLike for WriteBatchWithIndex {
+    fn write_to(self, db: &DB, writeopts: &WriteOptions) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_write_writebatch_wi(db.inner, writeopts.inner, self.inner));
+        }
+        Ok(())
+    }
+}
+
+/// Creates a DBVector from a value RocksDB returned, a null pointer means there is no value.
+pub(crate) unsafe fn to_db_vector(val: *mut u8, val_len: size_t) -> Option<DBVector> {
+    if val.is_null() {
+        None
+    } else {
+        Some(DBVector::from_c(val, val_len))
+    }
+}
+
 impl Drop for DB {
     fn drop(&mut self) {
         unsafe {
-            for cf in self.cfs.values() {
+            for cf in self.cfs.get_mut().unwrap().values() {
+                ffi::rocksdb_column_family_handle_destroy(cf.inner);
+            }
+            for cf in self.dropped_cfs.get_mut().unwrap().iter() {
                 ffi::rocksdb_column_family_handle_destroy(cf.inner);
             }
-            ffi::rocksdb_close(self.inner);
+            if self.optimistic_txn_db.is_null() {
+                ffi::rocksdb_close(self.inner);
+            } else {
+                // The base database is owned by the optimistic transaction database
+                ffi::rocksdb_optimistictransactiondb_close_base_db(self.inner);
+                ffi::rocksdb_optimistictransactiondb_close(self.optimistic_txn_db);
+            }
+            if !self.statistics.is_null() {
+                ffi::rocksdb_statistics_destroy(self.statistics);
+            }
         }
     }
 }
 
+impl<'a> ColumnFamily<'a> {
+    /// Returns the name of the column family.
+    pub fn name(&self) -> &str {
+        &self.state.name
+    }
+
+    /// Returns the number of dimensions if the column family was opened or created with
+    /// `Options::for_rtree`.
+    pub fn rtree_dimensions(&self) -> Option<usize> {
+        self.state.rtree_dimensions
+    }
+
+    /// Returns the coordinate type if the column family was opened or created with
+    /// `Options::for_rtree`.
+    pub fn rtree_coordinate_type(&self) -> Option<CoordinateType> {
+        self.state.rtree_coordinate_type
+    }
+}
+
 impl fmt::Debug for DB {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "RocksDB {{ path: {:?} }}", self.path())
@@ -1163,41 +2455,242 @@ impl Drop for ReadOptions {
 }
 
 impl ReadOptions {
-    // TODO add snapshot setting here
-    // TODO add snapshot wrapper structs with proper destructors;
-    // that struct needs an "iterator" impl too.
-    #[allow(dead_code)]
-    fn fill_cache(&mut self, v: bool) {
+    /// If `false`, the data read by this operation won't be put into the block cache.
+    ///
+    /// Default: `true`
+    pub fn fill_cache(&mut self, v: bool) {
         unsafe {
             ffi::rocksdb_readoptions_set_fill_cache(self.inner, v as c_uchar);
         }
     }
 
-    fn set_snapshot(&mut self, snapshot: &Snapshot) {
+    /// Reads at the given snapshot instead of the latest state of the database. Reading with
+    /// the read options fails with an error of kind `ErrorKind::InvalidArgument` once the
+    /// snapshot was dropped.
+    pub fn set_snapshot(&mut self, snapshot: &Snapshot) {
         unsafe {
             ffi::rocksdb_readoptions_set_snapshot(self.inner, snapshot.inner);
         }
+        self.snapshot = Some(Arc::downgrade(&snapshot.alive));
     }
 
+    /// Returns an error if the snapshot the read options were set to was dropped.
+    pub(crate) fn check_snapshot(&self) -> Result<(), Error> {
+        match self.snapshot {
+            Some(ref snapshot) if snapshot.upgrade().is_none() => {
+                Err(Error::with_kind(ErrorKind::InvalidArgument,
+                                     "The snapshot of the read options was dropped.".to_owned()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the key (exclusive) where iterators stop. The key is copied, so it doesn't need
+    /// to outlive the read options.
     pub fn set_iterate_upper_bound(&mut self, key: &[u8]) {
+        let key = key.to_vec();
         unsafe {
             ffi::rocksdb_readoptions_set_iterate_upper_bound(self.inner,
                                                              key.as_ptr() as *const c_char,
                                                              key.len() as size_t);
         }
+        self.iterate_upper_bound = Some(key);
+    }
+
+    /// Only return entries that intersect with the given R-tree query. The iterator context
+    /// is owned by the read options.
+    pub fn set_rtree_query<Q: ToMbb + ?Sized>(&mut self, query: &Q) {
+        self.set_iterator_context(RtreeIteratorContext::new(query));
     }
 
-    pub fn set_iterator_context<T: IteratorContext>(&mut self, context: &T) {
+    /// Sets the iterator context. It is owned by the read options, so that it lives as long
+    /// as the iterators that are created with them.
+    pub fn set_iterator_context(&mut self, context: RtreeIteratorContext) {
         unsafe {
             ffi::rocksdb_readoptions_set_iterator_context(self.inner, context.context());
         }
+        self.rtree_context = Some(context);
     }
 }
 
 impl Default for ReadOptions {
     fn default() -> ReadOptions {
-        unsafe { ReadOptions { inner: ffi::rocksdb_readoptions_create() } }
+        unsafe {
+            ReadOptions {
+                inner: ffi::rocksdb_readoptions_create(),
+                iterate_upper_bound: None,
+                rtree_context: None,
+                snapshot: None,
+            }
+        }
+    }
+}
+
+/// Copies a C string that is owned by RocksDB.
+unsafe fn c_str_to_string(ptr: *const c_char) -> String {
+    String::from_utf8_lossy(CStr::from_ptr(ptr).to_bytes()).into_owned()
+}
+
+/// The column families of a database that is about to be opened, in the form the RocksDB
+/// functions that open a database expect them.
+pub(crate) struct OpenArgs {
+    pub path: *const c_char,
+    pub num_cfs: c_int,
+    pub cf_names: *const *const c_char,
+    pub cf_opts: *const *const ffi::rocksdb_options_t,
+    pub cf_handles: *mut *mut ffi::rocksdb_column_family_handle_t,
+}
+
+/// Opens a database with the given column families, the default column family is always
+/// opened. The actual RocksDB function is called by `open`, `close` closes what it returned
+/// if a column family handle is missing.
+pub(crate) fn open_cf_raw<T, F>(opts: &Options,
+                                path: &Path,
+                                cfs: &[&str],
+                                cf_opts: &[&Options],
+                                create_dir: bool,
+                                open: F,
+                                close: unsafe extern "C" fn(*mut T))
+                                -> Result<(*mut T, BTreeMap<String, Box<ColumnFamilyState>>),
+                                          Error>
+    where F: FnOnce(&OpenArgs) -> Result<*mut T, Error>
+{
+    let cpath = match CString::new(path.to_string_lossy().as_bytes()) {
+        Ok(c) => c,
+        Err(_) => {
+            return Err(Error::with_kind(ErrorKind::CStringConversion,
+                                        "Failed to convert path to CString \
+                                         when opening DB."
+                                            .to_owned()))
+        }
+    };
+
+    if create_dir {
+        if let Err(e) = fs::create_dir_all(&path) {
+            return Err(Error::with_kind(ErrorKind::IOError,
+                                        format!("Failed to create RocksDB\
+                                                 directory: `{:?}`.",
+                                                e)));
+        }
+    }
+
+    if cfs.len() != cf_opts.len() {
+        return Err(Error::with_kind(ErrorKind::InvalidArgument,
+                                    format!("cfs.len() and cf_opts.len() do not match.")));
+    }
+
+    try!(check_rtree_setup(opts));
+    for cf_opt in cf_opts {
+        try!(check_rtree_setup(cf_opt));
+    }
+
+    let mut cfs_v = cfs.to_vec();
+    let mut cf_opts_v = cf_opts.to_vec();
+    // Always open the default column family
+    if !cfs_v.contains(&DEFAULT_COLUMN_FAMILY) {
+        cfs_v.push(DEFAULT_COLUMN_FAMILY);
+        cf_opts_v.push(opts);
+    }
+
+    // We need to store our CStrings in an intermediate vector
+    // so that their pointers remain valid.
+    let mut c_cfs: Vec<CString> = Vec::with_capacity(cfs_v.len());
+    for cf in &cfs_v {
+        match CString::new(cf.as_bytes()) {
+            Ok(c_cf) => c_cfs.push(c_cf),
+            Err(_) => {
+                return Err(Error::with_kind(ErrorKind::CStringConversion,
+                                            format!("Failed to convert column family \
+                                                     name to CString: {}",
+                                                    cf)))
+            }
+        }
+    }
+
+    let cfnames: Vec<*const _> = c_cfs.iter()
+        .map(|cf| cf.as_ptr())
+        .collect();
+
+    // These handles will be populated by DB.
+    let mut cfhandles: Vec<_> = cfs_v.iter()
+        .map(|_| ptr::null_mut())
+        .collect();
+
+    let cfopts: Vec<_> =
+        cf_opts_v.iter().map(|x| x.inner as *const _).collect();
+
+    let db = try!(open(&OpenArgs {
+        path: cpath.as_ptr(),
+        num_cfs: cfs_v.len() as c_int,
+        cf_names: cfnames.as_ptr(),
+        cf_opts: cfopts.as_ptr(),
+        cf_handles: cfhandles.as_mut_ptr(),
+    }));
+
+    if db.is_null() {
+        return Err(Error::with_kind(ErrorKind::NullHandle,
+                                    "Could not initialize database.".to_owned()));
+    }
+
+    // Don't leak the database and the other handles if a handle is missing
+    if cfhandles.iter().any(|handle| handle.is_null()) {
+        unsafe {
+            for handle in cfhandles.iter().filter(|handle| !handle.is_null()) {
+                ffi::rocksdb_column_family_handle_destroy(*handle);
+            }
+            close(db);
+        }
+        return Err(Error::with_kind(ErrorKind::NullHandle,
+                                    "Received null column family handle from DB."
+                                        .to_owned()));
+    }
+
+    let mut cf_map = BTreeMap::new();
+    for ((n, h), o) in cfs_v.iter().zip(cfhandles).zip(&cf_opts_v) {
+        cf_map.insert(n.to_string(), ColumnFamilyState::new(n, h, o));
     }
+
+    Ok((db, cf_map))
+}
+
+/// Opens the base database of an `OptimisticTransactionDB`, it's closed together with it.
+pub(crate) fn open_optimistic_transaction_db<P: AsRef<Path>>(opts: &Options,
+                                                                 path: P,
+                                                                 cfs: &[&str],
+                                                                 cf_opts: &[&Options])
+                                                                 -> Result<DB, Error> {
+    DB::open_cf_with_mode(opts, path, cfs, cf_opts, OpenMode::OptimisticTransaction)
+}
+
+pub(crate) fn null_readopts_error() -> Error {
+    Error::with_kind(ErrorKind::NullHandle,
+                     "Unable to create RocksDB read options. This is a fairly trivial call, \
+                      and its failure may be indicative of a mis-compiled or mis-loaded \
+                      RocksDB library."
+                         .to_owned())
+}
+
+/// Turns the output arrays of a multi get call into one result per key.
+fn multi_get_results(values_list: Vec<*mut c_char>,
+                     values_list_sizes: Vec<size_t>,
+                     errs: Vec<*mut ffi::rocksdb_error_t>)
+                     -> Vec<Result<Option<DBVector>, Error>> {
+    values_list.into_iter()
+        .zip(values_list_sizes)
+        .zip(errs)
+        .map(|((value, value_len), err)| unsafe {
+            if !err.is_null() {
+                if !value.is_null() {
+                    libc::free(value as *mut c_void);
+                }
+                Err(error_from_ffi(err))
+            } else if value.is_null() {
+                Ok(None)
+            } else {
+                Ok(Some(DBVector::from_c(value as *mut u8, value_len)))
+            }
+        })
+        .collect()
 }
 
 /// Vector of bytes stored in the database.
@@ -1292,6 +2785,7 @@ fn errors_do_stuff() {
     // The DB will still be open when we try to destroy it and the lock should fail.
     match DB::destroy(&opts, path) {
         Err(s) => {
+            assert_eq!(s.kind(), ErrorKind::IOError);
             let message = s.to_string();
             assert!(message.find("IO error:").is_some());
             assert!(message.find("_rust_rocksdb_error/LOCK:").is_some());
@@ -15,7 +15,7 @@
 
 
 use {BlockBasedOptions, BlockBasedIndexType, DBCompactionStyle, DBCompressionType, DBRecoveryMode,
-    Options, WriteOptions};
+    Error, ErrorKind, FlushOptions, Options, RtreeSetup, WriteOptions};
 use comparator::{self, ComparatorCallback, CompareFn};
 use ffi;
 
@@ -23,6 +23,8 @@ use libc::{self, c_int, c_uchar, c_uint, c_void, size_t, uint64_t};
 use merge_operator::{self, MergeFn, MergeOperatorCallback, full_merge_callback,
                      partial_merge_callback};
 use compaction_filter::{self, CompactionFilterCallback, CompactionFilterFn, filter_callback};
+use rtree::{self, CoordinateType};
+use statistics::Statistics;
 use std::ffi::{CStr, CString};
 use std::mem;
 
@@ -30,6 +32,27 @@ pub fn new_cache(capacity: size_t) -> *mut ffi::rocksdb_cache_t {
     unsafe { ffi::rocksdb_cache_create_lru(capacity) }
 }
 
+/// Returns an error if the options only contain parts of the setup an R-tree column family
+/// needs.
+pub fn check_rtree_setup(opts: &Options) -> Result<(), Error> {
+    let setup = &opts.rtree;
+    let parts = [(setup.memtable, "the skip list MBB memtable"),
+                 (setup.index, "the R-tree index type"),
+                 (setup.flush_block_policy, "the Noise flush block policy"),
+                 (setup.comparator, "the R-tree comparator")];
+    if parts.iter().all(|&(set, _)| set) || parts.iter().all(|&(set, _)| !set) {
+        return Ok(());
+    }
+    let missing: Vec<&str> = parts.iter()
+        .filter(|&&(set, _)| !set)
+        .map(|&(_, name)| name)
+        .collect();
+    Err(Error::with_kind(ErrorKind::InvalidArgument,
+                         format!("Incomplete R-tree column family options, missing {}. Use \
+                                  `Options::for_rtree` to set them up.",
+                                 missing.join(", "))))
+}
+
 impl Drop for Options {
     fn drop(&mut self) {
         unsafe {
@@ -38,6 +61,14 @@ impl Drop for Options {
     }
 }
 
+impl Drop for FlushOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_flushoptions_destroy(self.inner);
+        }
+    }
+}
+
 impl Drop for BlockBasedOptions {
     fn drop(&mut self) {
         unsafe {
@@ -89,12 +120,17 @@ impl BlockBasedOptions {
     }
 
     pub fn set_index_type(&mut self, v: BlockBasedIndexType) {
+        self.rtree_index = match v {
+            BlockBasedIndexType::RtreeSearch => true,
+            _ => false,
+        };
         unsafe {
             ffi::rocksdb_block_based_options_set_index_type(self.inner, v as c_int);
         }
     }
 
     pub fn set_flush_block_policy_noise(&mut self) {
+        self.noise_flush_block_policy = true;
         unsafe {
             ffi::rocksdb_block_based_options_set_flush_block_policy_noise(self.inner);
         }
@@ -107,11 +143,75 @@ impl Default for BlockBasedOptions {
         if block_opts.is_null() {
             panic!("Could not create RocksDB block based options");
         }
-        BlockBasedOptions { inner: block_opts }
+        BlockBasedOptions {
+            inner: block_opts,
+            rtree_index: false,
+            noise_flush_block_policy: false,
+        }
     }
 }
 
 impl Options {
+    /// Returns options for a column family that is indexed by an R-tree with the given number
+    /// of dimensions and `f64` coordinates.
+    ///
+    /// It sets up the memtable, the index type, the flush block policy and the comparator,
+    /// which all need to be used together. Keys can be created with `rtree::RtreeKey`.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if `dimensions` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::for_rtree(2);
+    /// opts.create_if_missing(true);
+    /// ```
+    pub fn for_rtree(dimensions: usize) -> Options {
+        Options::for_rtree_with_type(dimensions, CoordinateType::F64)
+    }
+
+    /// Like `Options::for_rtree`, but for keys with the given coordinate type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    /// use rocksdb::rtree::CoordinateType;
+    ///
+    /// // Time intervals stored as integer timestamps
+    /// let opts = Options::for_rtree_with_type(1, CoordinateType::I64);
+    /// ```
+    pub fn for_rtree_with_type(dimensions: usize, coordinate_type: CoordinateType) -> Options {
+        assert!(dimensions > 0, "an R-tree needs at least one dimension");
+        let mut opts = Options::default();
+        opts.set_memtable_skip_list_mbb_rep();
+
+        let mut block_based_opts = BlockBasedOptions::default();
+        block_based_opts.set_index_type(BlockBasedIndexType::RtreeSearch);
+        block_based_opts.set_flush_block_policy_noise();
+        opts.set_block_based_table_factory(&block_based_opts);
+
+        opts.set_comparator(&rtree::comparator_name(dimensions, coordinate_type),
+                            rtree::compare_keys);
+        opts
+    }
+
+    /// Returns the number of dimensions if the options were created with `Options::for_rtree`,
+    /// or if the comparator was set to one named by `rtree::comparator_name`.
+    pub fn rtree_dimensions(&self) -> Option<usize> {
+        self.rtree.dimensions
+    }
+
+    /// Returns the coordinate type if the options were created with `Options::for_rtree`,
+    /// or if the comparator was set to one named by `rtree::comparator_name`.
+    pub fn rtree_coordinate_type(&self) -> Option<CoordinateType> {
+        self.rtree.coordinate_type
+    }
+
     /// By default, RocksDB uses only one background thread for flush and
     /// compaction. Calling this function will set it up such that total of
     /// `total_threads` is used. Good value for `total_threads` is the number of
@@ -265,6 +365,10 @@ impl Options {
     /// name and orders keys *exactly* the same as the comparator provided to
     /// previous open calls on the same DB.
     pub fn set_comparator(&mut self, name: &str, compare_fn: CompareFn) {
+        let layout = rtree::comparator_layout(name);
+        self.rtree.dimensions = layout.map(|(dimensions, _)| dimensions);
+        self.rtree.coordinate_type = layout.map(|(_, coordinate_type)| coordinate_type);
+        self.rtree.comparator = name == rtree::COMPARATOR_NAME || layout.is_some();
         let cb = Box::new(ComparatorCallback {
             name: CString::new(name.as_bytes()).unwrap(),
             f: compare_fn,
@@ -848,6 +952,8 @@ impl Options {
     }
 
     pub fn set_block_based_table_factory(&mut self, factory: &BlockBasedOptions) {
+        self.rtree.index = factory.rtree_index;
+        self.rtree.flush_block_policy = factory.noise_flush_block_policy;
         unsafe {
             ffi::rocksdb_options_set_block_based_table_factory(self.inner, factory.inner);
         }
@@ -909,6 +1015,14 @@ impl Options {
         }
     }
 
+    /// Returns the parsed statistics, `None` if they aren't enabled.
+    pub fn statistics(&self) -> Result<Option<Statistics>, Error> {
+        match self.get_statistics() {
+            Some(stats) => Statistics::parse(&stats).map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// If not zero, dump `rocksdb.stats` to LOG every `stats_dump_period_sec`.
     ///
     /// Default: `600` (10 mins)
@@ -936,6 +1050,7 @@ impl Options {
 
     /// Sets the MemTable to the Mbb one
     pub fn set_memtable_skip_list_mbb_rep(&mut self) {
+        self.rtree.memtable = true;
         unsafe {
             ffi::rocksdb_options_set_memtable_skip_list_mbb_rep(self.inner);
         }
@@ -949,7 +1064,10 @@ impl Default for Options {
             if opts.is_null() {
                 panic!("Could not create RocksDB options");
             }
-            Options { inner: opts }
+            Options {
+                inner: opts,
+                rtree: RtreeSetup::default(),
+            }
         }
     }
 }
@@ -982,9 +1100,34 @@ impl Default for WriteOptions {
     }
 }
 
+impl FlushOptions {
+    pub fn new() -> FlushOptions {
+        FlushOptions::default()
+    }
+
+    /// Waits until the flush is done. It is on by default.
+    pub fn set_wait(&mut self, wait: bool) {
+        unsafe {
+            ffi::rocksdb_flushoptions_set_wait(self.inner, wait as c_uchar);
+        }
+    }
+}
+
+impl Default for FlushOptions {
+    fn default() -> FlushOptions {
+        let flush_opts = unsafe { ffi::rocksdb_flushoptions_create() };
+        if flush_opts.is_null() {
+            panic!("Could not create RocksDB flush options");
+        }
+        FlushOptions { inner: flush_opts }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use Options;
+    use {BlockBasedIndexType, BlockBasedOptions, Options};
+    use rtree::CoordinateType;
+    use super::check_rtree_setup;
 
     #[test]
     fn test_enable_statistics() {
@@ -996,4 +1139,25 @@ mod tests {
         let opts = Options::default();
         assert!(opts.get_statistics().is_none());
     }
+
+    #[test]
+    fn test_rtree_setup() {
+        let opts = Options::for_rtree(2);
+        assert_eq!(opts.rtree_dimensions(), Some(2));
+        assert!(check_rtree_setup(&opts).is_ok());
+        assert!(check_rtree_setup(&Options::default()).is_ok());
+
+        let opts = Options::for_rtree_with_type(3, CoordinateType::F32);
+        assert_eq!(opts.rtree_dimensions(), Some(3));
+        assert_eq!(opts.rtree_coordinate_type(), Some(CoordinateType::F32));
+        assert!(check_rtree_setup(&opts).is_ok());
+
+        let mut opts = Options::default();
+        opts.set_memtable_skip_list_mbb_rep();
+        let mut block_based_opts = BlockBasedOptions::default();
+        block_based_opts.set_index_type(BlockBasedIndexType::RtreeSearch);
+        opts.set_block_based_table_factory(&block_based_opts);
+        let message = check_rtree_setup(&opts).unwrap_err().to_string();
+        assert!(message.contains("the Noise flush block policy, the R-tree comparator"));
+    }
 }
@@ -13,17 +13,23 @@
 // limitations under the License.
 //
 
-use libc::{self, c_char, c_void};
+use Error;
+use ffi;
+use libc::c_char;
 use std::ffi::CStr;
 use std::ptr;
 
-pub fn error_message(ptr: *const c_char) -> String {
-    let cstr = unsafe { CStr::from_ptr(ptr as *const _) };
-    let s = String::from_utf8_lossy(cstr.to_bytes()).into_owned();
+/// Converts an error RocksDB returned into an `Error` and destroys it.
+pub fn error_from_ffi(err: *mut ffi::rocksdb_error_t) -> Error {
     unsafe {
-        libc::free(ptr as *mut c_void);
+        let cstr = CStr::from_ptr(ffi::rocksdb_error_message(err));
+        let message = String::from_utf8_lossy(cstr.to_bytes()).into_owned();
+        let error = Error::from_status(ffi::rocksdb_error_code(err),
+                                       ffi::rocksdb_error_subcode(err),
+                                       message);
+        ffi::rocksdb_error_destroy(err);
+        error
     }
-    s
 }
 
 pub fn opt_bytes_to_ptr(opt: Option<&[u8]>) -> *const c_char {
@@ -35,10 +41,10 @@ pub fn opt_bytes_to_ptr(opt: Option<&[u8]>) -> *const c_char {
 
 macro_rules! ffi_try {
     ( $($function:ident)::*( $( $arg:expr ),* ) ) => ({
-        let mut err: *mut ::libc::c_char = ::std::ptr::null_mut();
+        let mut err: *mut ::ffi::rocksdb_error_t = ::std::ptr::null_mut();
         let result = $($function)::*($($arg),*, &mut err);
         if !err.is_null() {
-            return Err(Error::new($crate::ffi_util::error_message(err)));
+            return Err($crate::ffi_util::error_from_ffi(err));
         }
         result
     })
@@ -38,41 +38,179 @@ extern crate librocksdb_sys as ffi;
 mod ffi_util;
 
 pub mod backup;
+pub mod checkpoint;
 mod comparator;
 pub mod merge_operator;
 pub mod compaction_filter;
 mod db;
 mod db_options;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+pub mod properties;
+pub mod rtree;
+pub mod statistics;
+pub mod transaction;
 
-pub use db::{DBCompactionStyle, DBCompressionType, DBIterator, DBRawIterator, DBRecoveryMode, DBVector,
-             ReadOptions, Direction, IteratorMode, Snapshot, WriteBatch, new_bloom_filter, RtreeIteratorContext};
+pub use db::{DBCompactionStyle, DBCompressionType, DBIterator, DBRawIterator, DBRecoveryMode,
+             DBVector, Direction, IteratorMode, LiveFile, Snapshot, WriteBatch, WriteBatchLike,
+             WriteBatchWithIndex, new_bloom_filter, RtreeIteratorContext};
 
 pub use merge_operator::MergeOperands;
 pub use compaction_filter::Decision as CompactionDecision;
+use libc::c_int;
 use std::collections::BTreeMap;
 use std::error;
 use std::fmt;
 use std::path::PathBuf;
+use std::sync::{Mutex, RwLock, Weak};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
 
 /// A RocksDB database.
 ///
 /// See crate level documentation for a simple usage example.
 pub struct DB {
     inner: *mut ffi::rocksdb_t,
-    cfs: BTreeMap<String, ColumnFamily>,
+    cfs: RwLock<BTreeMap<String, Box<ColumnFamilyState>>>,
+    // Handles of dropped column families are only destroyed when the database is closed, as
+    // there might still be `ColumnFamily` instances referring to them.
+    dropped_cfs: Mutex<Vec<Box<ColumnFamilyState>>>,
     path: PathBuf,
+    // The statistics of the options the database was opened with, null if they weren't enabled
+    statistics: *mut ffi::rocksdb_statistics_t,
+    // The optimistic transaction database this is the base database of, it's null otherwise
+    optimistic_txn_db: *mut ffi::rocksdb_optimistictransactiondb_t,
 }
 
-/// A simple wrapper round a string, used for errors reported from
-/// ffi calls.
+/// A RocksDB database that is opened read-only.
+///
+/// Only the read operations are available. Several processes may open the same database
+/// read-only, also while another one has it opened for writing. Changes written afterwards
+/// by others are not visible.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{DB, Options, ReadOnlyDB};
+///
+/// let path = "path/for/rocksdb/storage17";
+/// let db = DB::open_default(path).unwrap();
+/// db.put(b"my key", b"my value").unwrap();
+///
+/// let read_only_db = ReadOnlyDB::open(&Options::default(), path, false).unwrap();
+/// assert_eq!(&*read_only_db.get(b"my key").unwrap().unwrap(), b"my value");
+/// ```
+pub struct ReadOnlyDB {
+    db: DB,
+}
+
+/// An error reported from ffi calls or by the wrapper itself.
+///
+/// The kind of errors reported by RocksDB is derived from the code of their status.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{DB, ErrorKind, Options};
+///
+/// let path = "path/for/rocksdb/storage11";
+/// let _db = DB::open_default(path).unwrap();
+/// // The database is still open, hence it can't be destroyed
+/// let err = DB::destroy(&Options::default(), path).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::IOError);
+/// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct Error {
     message: String,
+    kind: ErrorKind,
+    subcode: ErrorSubCode,
+}
+
+/// The kind of an `Error`.
+///
+/// Most of them correspond to the codes of a RocksDB status, the others are for failures
+/// within the wrapper.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    NotFound,
+    Corruption,
+    NotSupported,
+    InvalidArgument,
+    IOError,
+    MergeInProgress,
+    Incomplete,
+    ShutdownInProgress,
+    TimedOut,
+    Aborted,
+    Busy,
+    Expired,
+    TryAgain,
+    /// A string couldn't be converted into a C string, e.g. as it contains a null byte.
+    CStringConversion,
+    /// RocksDB unexpectedly returned a null pointer.
+    NullHandle,
+    /// The error didn't match any of the other kinds.
+    Other,
+}
+
+/// The subcode of an `Error`, which gives more details for some kinds of errors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorSubCode {
+    None,
+    MutexTimeout,
+    LockTimeout,
+    LockLimit,
+    NoSpace,
 }
 
 impl Error {
-    fn new(message: String) -> Error {
-        Error { message: message }
+    /// Creates an error from the code, subcode and message of a RocksDB status.
+    fn from_status(code: c_int, subcode: c_int, message: String) -> Error {
+        // The values of `Status::Code` and `Status::SubCode` in `rocksdb/status.h`
+        let kind = match code {
+            1 => ErrorKind::NotFound,
+            2 => ErrorKind::Corruption,
+            3 => ErrorKind::NotSupported,
+            4 => ErrorKind::InvalidArgument,
+            5 => ErrorKind::IOError,
+            6 => ErrorKind::MergeInProgress,
+            7 => ErrorKind::Incomplete,
+            8 => ErrorKind::ShutdownInProgress,
+            9 => ErrorKind::TimedOut,
+            10 => ErrorKind::Aborted,
+            11 => ErrorKind::Busy,
+            12 => ErrorKind::Expired,
+            13 => ErrorKind::TryAgain,
+            _ => ErrorKind::Other,
+        };
+        let subcode = match subcode {
+            1 => ErrorSubCode::MutexTimeout,
+            2 => ErrorSubCode::LockTimeout,
+            3 => ErrorSubCode::LockLimit,
+            4 => ErrorSubCode::NoSpace,
+            _ => ErrorSubCode::None,
+        };
+        Error {
+            message: message,
+            kind: kind,
+            subcode: subcode,
+        }
+    }
+
+    /// Creates an error for failures within the wrapper.
+    fn with_kind(kind: ErrorKind, message: String) -> Error {
+        Error {
+            message: message,
+            kind: kind,
+            subcode: ErrorSubCode::None,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn subcode(&self) -> ErrorSubCode {
+        self.subcode
     }
 
     pub fn to_string(self) -> String {
@@ -115,6 +253,8 @@ pub enum BlockBasedIndexType {
 /// For configuring block-based file storage.
 pub struct BlockBasedOptions {
     inner: *mut ffi::rocksdb_block_based_table_options_t,
+    rtree_index: bool,
+    noise_flush_block_policy: bool,
 }
 
 /// Database-wide options around performance and behavior.
@@ -152,6 +292,20 @@ pub struct BlockBasedOptions {
 /// ```
 pub struct Options {
     inner: *mut ffi::rocksdb_options_t,
+    rtree: RtreeSetup,
+}
+
+/// Keeps track of which parts of an R-tree column family setup were applied to an `Options`.
+///
+/// All of them are needed, else queries return wrong results once data is flushed to disk.
+#[derive(Debug, Default, Copy, Clone)]
+struct RtreeSetup {
+    dimensions: Option<usize>,
+    coordinate_type: Option<rtree::CoordinateType>,
+    memtable: bool,
+    index: bool,
+    flush_block_policy: bool,
+    comparator: bool,
 }
 
 /// Optionally disable WAL or sync for this write.
@@ -180,9 +334,159 @@ pub struct WriteOptions {
     inner: *mut ffi::rocksdb_writeoptions_t,
 }
 
+pub struct ReadOptions {
+    inner: *mut ffi::rocksdb_readoptions_t,
+    // RocksDB only keeps pointers to those, hence they are owned by the read options
+    iterate_upper_bound: Option<Vec<u8>>,
+    rtree_context: Option<RtreeIteratorContext>,
+    // Set if the read options were set to a snapshot, to check that it's still alive
+    snapshot: Option<Weak<()>>,
+}
+
+/// Optionally wait for the memtable flush to be performed.
+///
+/// # Examples
+///
+/// Manually flushing the memtable:
+///
+/// ```
+/// use rocksdb::{DB, FlushOptions};
+///
+/// let db = DB::open_default("path/for/rocksdb/storage16").unwrap();
+/// db.put(b"my key", b"my value").unwrap();
+///
+/// let mut flush_options = FlushOptions::default();
+/// flush_options.set_wait(true);
+///
+/// db.flush_opt(&flush_options).unwrap();
+/// ```
+pub struct FlushOptions {
+    inner: *mut ffi::rocksdb_flushoptions_t,
+}
+
 /// An opaque type used to represent a column family. Returned from some functions, and used
-/// in others
+/// in others.
+///
+/// It borrows the database, so it can't outlive it. Using it after the column family was
+/// dropped with `DB::drop_cf` returns an error.
 #[derive(Copy, Clone)]
-pub struct ColumnFamily {
+pub struct ColumnFamily<'a> {
+    state: &'a ColumnFamilyState,
+}
+
+/// The state of a column family handle. It lives as long as the database.
+struct ColumnFamilyState {
+    name: String,
     inner: *mut ffi::rocksdb_column_family_handle_t,
+    dropped: AtomicBool,
+    rtree_dimensions: Option<usize>,
+    rtree_coordinate_type: Option<rtree::CoordinateType>,
+}
+
+impl<'a> ColumnFamily<'a> {
+    /// Returns the raw handle, or an error if the column family was dropped.
+    fn handle(&self) -> Result<*mut ffi::rocksdb_column_family_handle_t, Error> {
+        if self.state.dropped.load(AtomicOrdering::SeqCst) {
+            return Err(Error::with_kind(ErrorKind::InvalidArgument,
+                                        format!("Column family was dropped: {}",
+                                                self.state.name)));
+        }
+        Ok(self.state.inner)
+    }
+
+    /// Returns an error if the column family is indexed by an R-tree with a different number
+    /// of dimensions.
+    fn check_rtree_dimensions(&self, dimensions: usize) -> Result<(), Error> {
+        match self.state.rtree_dimensions {
+            Some(expected) if expected != dimensions => {
+                Err(Error::with_kind(ErrorKind::InvalidArgument,
+                                     format!("R-tree column family {} has {} dimensions, not \
+                                              {}.",
+                                             self.state.name,
+                                             expected,
+                                             dimensions)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns an error if the column family is indexed by an R-tree and the key isn't an
+    /// R-tree key with the same number of dimensions and coordinate type.
+    fn check_rtree_key(&self, key: &[u8]) -> Result<(), Error> {
+        match self.state.rtree_coordinate_type {
+            Some(coordinate_type) => {
+                try!(self.check_rtree_dimensions(try!(rtree::key_dimensions(key))));
+                rtree::check_key_coordinates(key, coordinate_type)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns an error if the column family is indexed by an R-tree and the query has a
+    /// different number of dimensions or another coordinate type.
+    fn check_rtree_query<Q: rtree::ToMbb + ?Sized>(&self, mbb: &Q) -> Result<(), Error> {
+        match (mbb.layout(), self.state.rtree_coordinate_type) {
+            (Some((dimensions, coordinate_type)), Some(expected)) => {
+                try!(self.check_rtree_dimensions(dimensions));
+                if coordinate_type != expected {
+                    return Err(Error::with_kind(ErrorKind::InvalidArgument,
+                                                format!("R-tree column family {} has {:?} \
+                                                         coordinates, not {:?}.",
+                                                        self.state.name,
+                                                        expected,
+                                                        coordinate_type)));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ColumnFamilyState {
+    fn new(name: &str,
+           inner: *mut ffi::rocksdb_column_family_handle_t,
+           opts: &Options)
+           -> Box<ColumnFamilyState> {
+        Box::new(ColumnFamilyState {
+            name: name.to_string(),
+            inner: inner,
+            dropped: AtomicBool::new(false),
+            rtree_dimensions: opts.rtree.dimensions,
+            rtree_coordinate_type: opts.rtree.coordinate_type,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Error, ErrorKind, ErrorSubCode};
+
+    #[test]
+    fn test_error_kind() {
+        let err = Error::from_status(1, 0, "NotFound: ".to_owned());
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(err.subcode(), ErrorSubCode::None);
+
+        let err = Error::from_status(2, 0, "Corruption: block checksum mismatch".to_owned());
+        assert_eq!(err.kind(), ErrorKind::Corruption);
+
+        let err = Error::from_status(9,
+                                     2,
+                                     "Operation timed out: Timeout waiting to lock key".to_owned());
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        assert_eq!(err.subcode(), ErrorSubCode::LockTimeout);
+
+        let err = Error::from_status(5, 4, "IO error: No space left on device".to_owned());
+        assert_eq!(err.kind(), ErrorKind::IOError);
+        assert_eq!(err.subcode(), ErrorSubCode::NoSpace);
+
+        let err = Error::from_status(13, 0, "Operation failed. Try again.: ".to_owned());
+        assert_eq!(err.kind(), ErrorKind::TryAgain);
+
+        let err = Error::from_status(42, 42, "Something unexpected".to_owned());
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert_eq!(err.subcode(), ErrorSubCode::None);
+        assert_eq!(err.to_string(), "Something unexpected");
+    }
 }
@@ -0,0 +1,279 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Renders metrics of databases in the Prometheus text exposition format.
+//!
+//! This module is only available with the `prometheus` feature. Serving the output over
+//! HTTP is left to the application.
+//!
+//! # Examples
+//!
+//! ```
+//! use rocksdb::{DB, Options};
+//! use rocksdb::prometheus;
+//!
+//! let mut opts = Options::default();
+//! opts.create_if_missing(true);
+//! opts.enable_statistics();
+//! let db = DB::open(&opts, "path/for/rocksdb/storage14").unwrap();
+//! let other_db = DB::open_default("path/for/rocksdb/storage15").unwrap();
+//!
+//! let metrics = prometheus::export(&[("main", &db), ("other", &other_db)]).unwrap();
+//! assert!(metrics.contains("rocksdb_estimate_num_keys{db=\"main\",cf=\"default\"} 0"));
+//! ```
+
+use {DB, ColumnFamily, Error};
+use properties::Property;
+use statistics::Statistics;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// The integer properties that are exported for every column family.
+pub const EXPORTED_PROPERTIES: &'static [Property] =
+    &[Property::EstimateNumKeys,
+      Property::EstimateLiveDataSize,
+      Property::TotalSstFilesSize,
+      Property::CurSizeActiveMemTable,
+      Property::CurSizeAllMemTables,
+      Property::SizeAllMemTables,
+      Property::NumEntriesActiveMemTable,
+      Property::NumDeletesActiveMemTable,
+      Property::NumImmutableMemTable,
+      Property::MemTableFlushPending,
+      Property::NumRunningFlushes,
+      Property::CompactionPending,
+      Property::NumRunningCompactions,
+      Property::EstimatePendingCompactionBytes,
+      Property::EstimateTableReadersMem,
+      Property::BackgroundErrors,
+      Property::NumSnapshots,
+      Property::NumLiveVersions,
+      Property::ActualDelayedWriteRate,
+      Property::IsWriteStopped];
+
+// RocksDB doesn't support more levels than that
+const MAX_LEVELS: usize = 100;
+
+/// The metrics of a single database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbMetrics {
+    /// The value of the `db` label.
+    pub name: String,
+    /// The statistics, if they are enabled.
+    pub statistics: Option<Statistics>,
+    pub cfs: Vec<CfMetrics>,
+}
+
+/// The metrics of a single column family.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfMetrics {
+    /// The value of the `cf` label.
+    pub name: String,
+    /// The values of the properties, properties RocksDB doesn't know about are left out.
+    pub properties: Vec<(Property, u64)>,
+    /// The number of files, the index is the level.
+    pub num_files_at_level: Vec<u64>,
+}
+
+impl DbMetrics {
+    /// Collects the statistics and the properties of all column families of a database.
+    pub fn collect(name: &str, db: &DB) -> Result<DbMetrics, Error> {
+        let mut cfs = vec![try!(CfMetrics::collect(db, None))];
+        let cf_names: Vec<String> = db.cfs.read().unwrap().keys().cloned().collect();
+        for cf_name in cf_names.iter().filter(|cf_name| *cf_name != "default") {
+            // The column family might have been dropped in the meantime
+            if let Some(cf) = db.cf_handle(cf_name) {
+                cfs.push(try!(CfMetrics::collect(db, Some(cf))));
+            }
+        }
+        Ok(DbMetrics {
+            name: name.to_owned(),
+            statistics: try!(db.statistics()),
+            cfs: cfs,
+        })
+    }
+}
+
+impl CfMetrics {
+    /// Collects the properties of a column family, of the default one if `cf` is `None`.
+    pub fn collect(db: &DB, cf: Option<ColumnFamily>) -> Result<CfMetrics, Error> {
+        let property_int_value = |property: &Property| match cf {
+            Some(cf) => db.property_int_value_cf(cf, property),
+            None => db.property_int_value(property),
+        };
+
+        let mut properties = Vec::new();
+        for property in EXPORTED_PROPERTIES {
+            if let Some(value) = try!(property_int_value(property)) {
+                properties.push((*property, value));
+            }
+        }
+        let mut num_files_at_level = Vec::new();
+        for level in 0..MAX_LEVELS {
+            match try!(property_int_value(&Property::NumFilesAtLevel(level))) {
+                Some(num_files) => num_files_at_level.push(num_files),
+                None => break,
+            }
+        }
+        Ok(CfMetrics {
+            name: match cf {
+                Some(cf) => cf.name().to_owned(),
+                None => "default".to_owned(),
+            },
+            properties: properties,
+            num_files_at_level: num_files_at_level,
+        })
+    }
+}
+
+/// Collects the metrics of the given databases, which are labeled with the given names, and
+/// renders them.
+pub fn export(dbs: &[(&str, &DB)]) -> Result<String, Error> {
+    let mut metrics = Vec::with_capacity(dbs.len());
+    for &(name, db) in dbs {
+        metrics.push(try!(DbMetrics::collect(name, db)));
+    }
+    Ok(render(&metrics))
+}
+
+/// Renders already collected metrics in the Prometheus text exposition format.
+pub fn render(dbs: &[DbMetrics]) -> String {
+    let mut families = MetricFamilies::default();
+    for db in dbs {
+        let db_label = [("db", db.name.clone())];
+        if let Some(ref statistics) = db.statistics {
+            for (name, value) in statistics.tickers() {
+                let family = families.get(&format!("{}_total", metric_name(name)),
+                                          "counter",
+                                          &format!("RocksDB ticker {}.", name));
+                family.sample("", &db_label, &value.to_string());
+            }
+            for (name, histogram) in statistics.histograms() {
+                let family = families.get(&metric_name(name),
+                                          "summary",
+                                          &format!("RocksDB histogram {}.", name));
+                let quantiles = [("0.5", histogram.p50),
+                                 ("0.95", histogram.p95),
+                                 ("0.99", histogram.p99),
+                                 ("1", histogram.p100)];
+                for &(quantile, value) in &quantiles {
+                    let labels = [("db", db.name.clone()), ("quantile", quantile.to_owned())];
+                    family.sample("", &labels, &value.to_string());
+                }
+                if let Some(sum) = histogram.sum {
+                    family.sample("_sum", &db_label, &sum.to_string());
+                }
+                if let Some(count) = histogram.count {
+                    family.sample("_count", &db_label, &count.to_string());
+                }
+            }
+        }
+
+        for cf in &db.cfs {
+            let cf_labels = [("db", db.name.clone()), ("cf", cf.name.clone())];
+            for &(property, value) in &cf.properties {
+                let name = property.name();
+                let family = families.get(&metric_name(&name),
+                                          "gauge",
+                                          &format!("RocksDB property {}.", name));
+                family.sample("", &cf_labels, &value.to_string());
+            }
+            for (level, num_files) in cf.num_files_at_level.iter().enumerate() {
+                let family = families.get("rocksdb_num_files_at_level",
+                                          "gauge",
+                                          "RocksDB property rocksdb.num-files-at-level<N>.");
+                let labels = [("db", db.name.clone()),
+                              ("cf", cf.name.clone()),
+                              ("level", level.to_string())];
+                family.sample("", &labels, &num_files.to_string());
+            }
+        }
+    }
+    families.render()
+}
+
+/// Turns a RocksDB name like `rocksdb.block.cache.miss` into a valid metric name like
+/// `rocksdb_block_cache_miss`.
+fn metric_name(name: &str) -> String {
+    let name = if name.starts_with("rocksdb.") {
+        &name["rocksdb.".len()..]
+    } else {
+        name
+    };
+    let mut metric_name = "rocksdb_".to_owned();
+    metric_name.extend(name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }));
+    metric_name
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Metric families in the order they were first seen, so that the samples of all databases
+/// end up below a single `TYPE` line.
+#[derive(Default)]
+struct MetricFamilies {
+    families: Vec<MetricFamily>,
+    indexes: BTreeMap<String, usize>,
+}
+
+struct MetricFamily {
+    name: String,
+    metric_type: &'static str,
+    help: String,
+    samples: String,
+}
+
+impl MetricFamilies {
+    fn get(&mut self, name: &str, metric_type: &'static str, help: &str) -> &mut MetricFamily {
+        let families = &mut self.families;
+        let index = *self.indexes.entry(name.to_owned()).or_insert_with(|| {
+            families.push(MetricFamily {
+                name: name.to_owned(),
+                metric_type: metric_type,
+                help: help.to_owned(),
+                samples: String::new(),
+            });
+            families.len() - 1
+        });
+        &mut self.families[index]
+    }
+
+    fn render(&self) -> String {
+        let mut output = String::new();
+        for family in &self.families {
+            writeln!(output, "# HELP {} {}", family.name, family.help).unwrap();
+            writeln!(output, "# TYPE {} {}", family.name, family.metric_type).unwrap();
+            output.push_str(&family.samples);
+        }
+        output
+    }
+}
+
+impl MetricFamily {
+    fn sample(&mut self, suffix: &str, labels: &[(&str, String)], value: &str) {
+        let labels: Vec<String> = labels.iter()
+            .map(|&(name, ref value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+            .collect();
+        writeln!(self.samples,
+                 "{}{}{{{}}} {}",
+                 self.name,
+                 suffix,
+                 labels.join(","),
+                 value)
+            .unwrap();
+    }
+}
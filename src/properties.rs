@@ -0,0 +1,221 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Well-known database properties that can be queried with `DB::property_value` and
+//! `DB::property_int_value`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rocksdb::DB;
+//! use rocksdb::properties::Property;
+//!
+//! let db = DB::open_default("path/for/rocksdb/storage12").unwrap();
+//! db.put(b"my key", b"my value").unwrap();
+//! let num_keys = db.property_int_value(&Property::EstimateNumKeys).unwrap();
+//! assert_eq!(num_keys, Some(1));
+//! let files = db.property_int_value(&Property::NumFilesAtLevel(0)).unwrap();
+//! assert_eq!(files, Some(0));
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// A property that RocksDB knows about, see `include/rocksdb/db.h` for details.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Property {
+    /// `rocksdb.num-files-at-level<N>`: number of files at the given level.
+    NumFilesAtLevel(usize),
+    /// `rocksdb.compression-ratio-at-level<N>`: compression ratio of the given level.
+    CompressionRatioAtLevel(usize),
+    /// `rocksdb.stats`: multi-line string with general statistics.
+    Stats,
+    /// `rocksdb.sstables`: multi-line string describing the SST files.
+    SsTables,
+    /// `rocksdb.cfstats`: multi-line string with column family statistics.
+    CfStats,
+    /// `rocksdb.dbstats`: multi-line string with database statistics.
+    DbStats,
+    /// `rocksdb.levelstats`: multi-line string with the number and size of files per level.
+    LevelStats,
+    /// `rocksdb.num-immutable-mem-table`: number of immutable memtables not flushed yet.
+    NumImmutableMemTable,
+    /// `rocksdb.num-immutable-mem-table-flushed`: number of immutable memtables flushed.
+    NumImmutableMemTableFlushed,
+    /// `rocksdb.mem-table-flush-pending`: 1 if a memtable flush is pending, else 0.
+    MemTableFlushPending,
+    /// `rocksdb.num-running-flushes`: number of currently running flushes.
+    NumRunningFlushes,
+    /// `rocksdb.compaction-pending`: 1 if at least one compaction is pending, else 0.
+    CompactionPending,
+    /// `rocksdb.num-running-compactions`: number of currently running compactions.
+    NumRunningCompactions,
+    /// `rocksdb.background-errors`: accumulated number of background errors.
+    BackgroundErrors,
+    /// `rocksdb.cur-size-active-mem-table`: approximate size of the active memtable in bytes.
+    CurSizeActiveMemTable,
+    /// `rocksdb.cur-size-all-mem-tables`: approximate size of the active and unflushed
+    /// immutable memtables in bytes.
+    CurSizeAllMemTables,
+    /// `rocksdb.size-all-mem-tables`: approximate size of all memtables, including pinned
+    /// ones, in bytes.
+    SizeAllMemTables,
+    /// `rocksdb.num-entries-active-mem-table`: number of entries in the active memtable.
+    NumEntriesActiveMemTable,
+    /// `rocksdb.num-entries-imm-mem-tables`: number of entries in the unflushed immutable
+    /// memtables.
+    NumEntriesImmMemTables,
+    /// `rocksdb.num-deletes-active-mem-table`: number of deletes in the active memtable.
+    NumDeletesActiveMemTable,
+    /// `rocksdb.num-deletes-imm-mem-tables`: number of deletes in the unflushed immutable
+    /// memtables.
+    NumDeletesImmMemTables,
+    /// `rocksdb.estimate-num-keys`: estimated number of keys.
+    EstimateNumKeys,
+    /// `rocksdb.estimate-table-readers-mem`: estimated memory used for reading SST files,
+    /// excluding the block cache.
+    EstimateTableReadersMem,
+    /// `rocksdb.is-file-deletions-enabled`: 0 if file deletions are enabled.
+    IsFileDeletionsEnabled,
+    /// `rocksdb.num-snapshots`: number of unreleased snapshots.
+    NumSnapshots,
+    /// `rocksdb.oldest-snapshot-time`: unix timestamp of the oldest unreleased snapshot.
+    OldestSnapshotTime,
+    /// `rocksdb.num-live-versions`: number of live versions.
+    NumLiveVersions,
+    /// `rocksdb.current-super-version-number`: number of the current super version.
+    CurrentSuperVersionNumber,
+    /// `rocksdb.estimate-live-data-size`: estimated size of the live data in bytes.
+    EstimateLiveDataSize,
+    /// `rocksdb.min-log-number-to-keep`: minimum log number that should be kept.
+    MinLogNumberToKeep,
+    /// `rocksdb.total-sst-files-size`: total size of all SST files in bytes.
+    TotalSstFilesSize,
+    /// `rocksdb.base-level`: level to which level 0 data is compacted.
+    BaseLevel,
+    /// `rocksdb.estimate-pending-compaction-bytes`: estimated number of bytes compaction
+    /// needs to rewrite.
+    EstimatePendingCompactionBytes,
+    /// `rocksdb.aggregated-table-properties`: aggregated table properties as string.
+    AggregatedTableProperties,
+    /// `rocksdb.aggregated-table-properties-at-level<N>`: aggregated table properties of the
+    /// given level as string.
+    AggregatedTablePropertiesAtLevel(usize),
+    /// `rocksdb.actual-delayed-write-rate`: current actual delayed write rate, 0 means no
+    /// delay.
+    ActualDelayedWriteRate,
+    /// `rocksdb.is-write-stopped`: 1 if writes have been stopped.
+    IsWriteStopped,
+}
+
+impl Property {
+    /// Returns the name RocksDB uses for the property.
+    pub fn name(&self) -> Cow<'static, str> {
+        match *self {
+            Property::NumFilesAtLevel(level) => {
+                Cow::Owned(format!("rocksdb.num-files-at-level{}", level))
+            }
+            Property::CompressionRatioAtLevel(level) => {
+                Cow::Owned(format!("rocksdb.compression-ratio-at-level{}", level))
+            }
+            Property::Stats => Cow::Borrowed("rocksdb.stats"),
+            Property::SsTables => Cow::Borrowed("rocksdb.sstables"),
+            Property::CfStats => Cow::Borrowed("rocksdb.cfstats"),
+            Property::DbStats => Cow::Borrowed("rocksdb.dbstats"),
+            Property::LevelStats => Cow::Borrowed("rocksdb.levelstats"),
+            Property::NumImmutableMemTable => Cow::Borrowed("rocksdb.num-immutable-mem-table"),
+            Property::NumImmutableMemTableFlushed => {
+                Cow::Borrowed("rocksdb.num-immutable-mem-table-flushed")
+            }
+            Property::MemTableFlushPending => Cow::Borrowed("rocksdb.mem-table-flush-pending"),
+            Property::NumRunningFlushes => Cow::Borrowed("rocksdb.num-running-flushes"),
+            Property::CompactionPending => Cow::Borrowed("rocksdb.compaction-pending"),
+            Property::NumRunningCompactions => Cow::Borrowed("rocksdb.num-running-compactions"),
+            Property::BackgroundErrors => Cow::Borrowed("rocksdb.background-errors"),
+            Property::CurSizeActiveMemTable => Cow::Borrowed("rocksdb.cur-size-active-mem-table"),
+            Property::CurSizeAllMemTables => Cow::Borrowed("rocksdb.cur-size-all-mem-tables"),
+            Property::SizeAllMemTables => Cow::Borrowed("rocksdb.size-all-mem-tables"),
+            Property::NumEntriesActiveMemTable => {
+                Cow::Borrowed("rocksdb.num-entries-active-mem-table")
+            }
+            Property::NumEntriesImmMemTables => Cow::Borrowed("rocksdb.num-entries-imm-mem-tables"),
+            Property::NumDeletesActiveMemTable => {
+                Cow::Borrowed("rocksdb.num-deletes-active-mem-table")
+            }
+            Property::NumDeletesImmMemTables => Cow::Borrowed("rocksdb.num-deletes-imm-mem-tables"),
+            Property::EstimateNumKeys => Cow::Borrowed("rocksdb.estimate-num-keys"),
+            Property::EstimateTableReadersMem => {
+                Cow::Borrowed("rocksdb.estimate-table-readers-mem")
+            }
+            Property::IsFileDeletionsEnabled => Cow::Borrowed("rocksdb.is-file-deletions-enabled"),
+            Property::NumSnapshots => Cow::Borrowed("rocksdb.num-snapshots"),
+            Property::OldestSnapshotTime => Cow::Borrowed("rocksdb.oldest-snapshot-time"),
+            Property::NumLiveVersions => Cow::Borrowed("rocksdb.num-live-versions"),
+            Property::CurrentSuperVersionNumber => {
+                Cow::Borrowed("rocksdb.current-super-version-number")
+            }
+            Property::EstimateLiveDataSize => Cow::Borrowed("rocksdb.estimate-live-data-size"),
+            Property::MinLogNumberToKeep => Cow::Borrowed("rocksdb.min-log-number-to-keep"),
+            Property::TotalSstFilesSize => Cow::Borrowed("rocksdb.total-sst-files-size"),
+            Property::BaseLevel => Cow::Borrowed("rocksdb.base-level"),
+            Property::EstimatePendingCompactionBytes => {
+                Cow::Borrowed("rocksdb.estimate-pending-compaction-bytes")
+            }
+            Property::AggregatedTableProperties => {
+                Cow::Borrowed("rocksdb.aggregated-table-properties")
+            }
+            Property::AggregatedTablePropertiesAtLevel(level) => {
+                Cow::Owned(format!("rocksdb.aggregated-table-properties-at-level{}", level))
+            }
+            Property::ActualDelayedWriteRate => Cow::Borrowed("rocksdb.actual-delayed-write-rate"),
+            Property::IsWriteStopped => Cow::Borrowed("rocksdb.is-write-stopped"),
+        }
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.name().fmt(formatter)
+    }
+}
+
+/// Anything that can be used as name of a property, either a `Property` or a plain string.
+pub trait PropertyName {
+    fn property_name<'a>(&'a self) -> Cow<'a, str>;
+}
+
+impl PropertyName for Property {
+    fn property_name<'a>(&'a self) -> Cow<'a, str> {
+        self.name()
+    }
+}
+
+impl PropertyName for str {
+    fn property_name<'a>(&'a self) -> Cow<'a, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl PropertyName for String {
+    fn property_name<'a>(&'a self) -> Cow<'a, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<'a, T: PropertyName + ?Sized> PropertyName for &'a T {
+    fn property_name<'b>(&'b self) -> Cow<'b, str> {
+        (**self).property_name()
+    }
+}
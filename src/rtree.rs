@@ -0,0 +1,657 @@
+// Copyright 2017 Volker Mische
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Keys, queries and the comparator for column families using the R-tree index.
+//!
+//! An R-tree key consists of a keypath (prefixed by its length as varint), an
+//! internal id and a bounding box. The bounding box is stored as a minimum and a
+//! maximum value per dimension, so any number of dimensions is possible. All numbers
+//! are encoded as little-endian.
+//!
+//! The coordinates are either `f64`, `f32` or `i64` values. The R-tree index decodes
+//! coordinates as `f64`, hence they are always stored as such. `f32` values convert
+//! without loss, `i64` values need to be within ±2<sup>53</sup>.
+//!
+//! # Examples
+//!
+//! ```
+//! use rocksdb::rtree::{RtreeKey, RtreeQuery};
+//!
+//! let key = RtreeKey::new("somekeypath", 10, vec![10.75, 11.11, 48.24, 48.50]);
+//! let encoded = key.to_bytes();
+//! assert_eq!(RtreeKey::from_bytes(&encoded).unwrap(), key);
+//! assert_eq!(key.dimensions(), 2);
+//!
+//! // A one dimensional interval with integer coordinates
+//! let query = RtreeQuery::<i64>::new("somekeypath", (2, 15), vec![1500, 2000]);
+//! assert!(!query.to_bytes().is_empty());
+//! ```
+
+use {Error, ErrorKind};
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str;
+
+/// The name of the comparator returned by `compare_keys`.
+pub const COMPARATOR_NAME: &'static str = "noise_rtree_cmp";
+
+/// The largest magnitude of an `i64` coordinate, larger ones can't be stored as `f64`
+/// without loss.
+const MAX_EXACT_I64: i64 = 1 << 53;
+
+/// The type of the coordinates of the bounding boxes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CoordinateType {
+    F64,
+    F32,
+    I64,
+}
+
+impl CoordinateType {
+    /// Returns whether a stored coordinate is a valid value of this type.
+    fn is_valid(&self, value: f64) -> bool {
+        match *self {
+            CoordinateType::F64 => true,
+            CoordinateType::F32 => f32::from_f64(value).is_some(),
+            CoordinateType::I64 => i64::from_f64(value).is_some(),
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match *self {
+            CoordinateType::F64 => "",
+            CoordinateType::F32 => "_f32",
+            CoordinateType::I64 => "_i64",
+        }
+    }
+}
+
+/// A value that can be used as coordinate of a bounding box.
+pub trait Coordinate: Copy + PartialOrd + fmt::Debug {
+    fn coordinate_type() -> CoordinateType;
+
+    /// Returns the value as it is stored, `None` if it can't be stored without loss.
+    fn to_f64(&self) -> Option<f64>;
+
+    /// Returns the closest `f64` value. Rounding query bounds doesn't change which stored
+    /// values are within them.
+    fn to_f64_rounded(&self) -> f64;
+
+    /// Returns the stored value as this type, `None` if it isn't one.
+    fn from_f64(value: f64) -> Option<Self>;
+}
+
+impl Coordinate for f64 {
+    fn coordinate_type() -> CoordinateType {
+        CoordinateType::F64
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(*self)
+    }
+
+    fn to_f64_rounded(&self) -> f64 {
+        *self
+    }
+
+    fn from_f64(value: f64) -> Option<f64> {
+        Some(value)
+    }
+}
+
+impl Coordinate for f32 {
+    fn coordinate_type() -> CoordinateType {
+        CoordinateType::F32
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
+
+    fn to_f64_rounded(&self) -> f64 {
+        *self as f64
+    }
+
+    fn from_f64(value: f64) -> Option<f32> {
+        if value.is_nan() || (value as f32) as f64 == value {
+            Some(value as f32)
+        } else {
+            None
+        }
+    }
+}
+
+impl Coordinate for i64 {
+    fn coordinate_type() -> CoordinateType {
+        CoordinateType::I64
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        if *self >= -MAX_EXACT_I64 && *self <= MAX_EXACT_I64 {
+            Some(*self as f64)
+        } else {
+            None
+        }
+    }
+
+    fn to_f64_rounded(&self) -> f64 {
+        *self as f64
+    }
+
+    fn from_f64(value: f64) -> Option<i64> {
+        if value.fract() == 0.0 && value.abs() <= MAX_EXACT_I64 as f64 {
+            Some(value as i64)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the name `Options::for_rtree_with_type` uses for the comparator of a column family
+/// with the given number of dimensions and coordinate type.
+///
+/// RocksDB stores the name of the comparator with the column family and refuses to open it
+/// with a different one, hence it can't be opened with another number of dimensions or
+/// another coordinate type.
+pub fn comparator_name(dimensions: usize, coordinate_type: CoordinateType) -> String {
+    format!("{}_{}d{}", COMPARATOR_NAME, dimensions, coordinate_type.suffix())
+}
+
+/// Returns the number of dimensions and the coordinate type of a comparator name created by
+/// `comparator_name`.
+pub fn comparator_layout(name: &str) -> Option<(usize, CoordinateType)> {
+    if !name.starts_with(COMPARATOR_NAME) || !name[COMPARATOR_NAME.len()..].starts_with('_') {
+        return None;
+    }
+    let layout = &name[COMPARATOR_NAME.len() + 1..];
+    [CoordinateType::F64, CoordinateType::F32, CoordinateType::I64]
+        .iter()
+        .filter_map(|coordinate_type| {
+            let suffix = format!("d{}", coordinate_type.suffix());
+            if !layout.ends_with(&suffix) {
+                return None;
+            }
+            match layout[..layout.len() - suffix.len()].parse() {
+                Ok(dimensions) if dimensions > 0 => Some((dimensions, *coordinate_type)),
+                _ => None,
+            }
+        })
+        .next()
+}
+
+/// Returns the number of dimensions of an encoded R-tree key.
+pub fn key_dimensions(data: &[u8]) -> Result<usize, Error> {
+    let (_, offset) = try!(decode_keypath(data));
+    try!(decode_u64(data, offset));
+    let bbox_len = data.len() - offset - 8;
+    if bbox_len % 16 != 0 {
+        return Err(invalid(format!("R-tree bounding box has an invalid size of {} bytes.",
+                                   bbox_len)));
+    }
+    Ok(bbox_len / 16)
+}
+
+/// Returns an error if a coordinate of an encoded R-tree key isn't a valid value of the given
+/// type, e.g. a fractional value for `i64` coordinates.
+pub fn check_key_coordinates(data: &[u8], coordinate_type: CoordinateType) -> Result<(), Error> {
+    let (_, offset) = try!(decode_keypath(data));
+    try!(decode_u64(data, offset));
+    try!(decode_bbox_values(&data[offset + 8..]))
+        .into_iter()
+        .find(|value| !coordinate_type.is_valid(*value))
+        .map_or(Ok(()), |value| Err(invalid_coordinate(value, coordinate_type)))
+}
+
+/// A key stored in an R-tree column family.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtreeKey<T: Coordinate = f64> {
+    pub keypath: String,
+    pub iid: u64,
+    /// The minimum and maximum value for each dimension, e.g.
+    /// `[x_min, x_max, y_min, y_max]`.
+    pub bbox: Vec<T>,
+}
+
+/// A query for a window within an R-tree column family.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtreeQuery<T: Coordinate = f64> {
+    pub keypath: String,
+    /// The minimum and maximum (both inclusive) internal id.
+    pub iid_range: (u64, u64),
+    /// The minimum and maximum value for each dimension, e.g.
+    /// `[x_min, x_max, y_min, y_max]`.
+    pub bbox: Vec<T>,
+}
+
+/// Anything that can be used as the minimum bounding box of an R-tree query.
+pub trait ToMbb {
+    fn to_mbb<'a>(&'a self) -> Cow<'a, [u8]>;
+
+    /// Returns the number of dimensions and the coordinate type, if they are known.
+    fn layout(&self) -> Option<(usize, CoordinateType)> {
+        None
+    }
+}
+
+impl<T: Coordinate> RtreeKey<T> {
+    pub fn new(keypath: &str, iid: u64, bbox: Vec<T>) -> RtreeKey<T> {
+        RtreeKey {
+            keypath: keypath.to_string(),
+            iid: iid,
+            bbox: bbox,
+        }
+    }
+
+    /// Returns the number of dimensions of the bounding box.
+    pub fn dimensions(&self) -> usize {
+        self.bbox.len() / 2
+    }
+
+    /// Returns the key in the format the R-tree index expects.
+    ///
+    /// # Panics
+    ///
+    /// * Panics if an `i64` coordinate is outside of ±2<sup>53</sup>, it can't be stored
+    ///   without loss.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut key = Vec::with_capacity(10 + self.keypath.len() + 8 + self.bbox.len() * 8);
+        encode_keypath(&mut key, &self.keypath);
+        key.extend_from_slice(&self.iid.to_le_bytes());
+        for value in &self.bbox {
+            match value.to_f64() {
+                Some(value) => encode_f64(&mut key, value),
+                None => panic!("R-tree coordinate {:?} can't be stored as f64 without loss", value),
+            }
+        }
+        key
+    }
+
+    /// Decodes a key that was encoded with `to_bytes`.
+    pub fn from_bytes(data: &[u8]) -> Result<RtreeKey<T>, Error> {
+        let (keypath, offset) = try!(decode_keypath(data));
+        let iid = try!(decode_u64(data, offset));
+        let bbox = try!(decode_bbox(&data[offset + 8..]));
+        Ok(RtreeKey {
+            keypath: keypath.to_string(),
+            iid: iid,
+            bbox: bbox,
+        })
+    }
+}
+
+impl<T: Coordinate> RtreeQuery<T> {
+    pub fn new(keypath: &str, iid_range: (u64, u64), bbox: Vec<T>) -> RtreeQuery<T> {
+        RtreeQuery {
+            keypath: keypath.to_string(),
+            iid_range: iid_range,
+            bbox: bbox,
+        }
+    }
+
+    /// Returns the number of dimensions of the bounding box.
+    pub fn dimensions(&self) -> usize {
+        self.bbox.len() / 2
+    }
+
+    /// Returns the query in the format the R-tree index expects.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut query = Vec::with_capacity(10 + self.keypath.len() + 16 + self.bbox.len() * 8);
+        encode_keypath(&mut query, &self.keypath);
+        query.extend_from_slice(&self.iid_range.0.to_le_bytes());
+        query.extend_from_slice(&self.iid_range.1.to_le_bytes());
+        for value in &self.bbox {
+            encode_f64(&mut query, value.to_f64_rounded());
+        }
+        query
+    }
+
+    /// Decodes a query that was encoded with `to_bytes`.
+    pub fn from_bytes(data: &[u8]) -> Result<RtreeQuery<T>, Error> {
+        let (keypath, offset) = try!(decode_keypath(data));
+        let iid_min = try!(decode_u64(data, offset));
+        let iid_max = try!(decode_u64(data, offset + 8));
+        let bbox = try!(decode_bbox(&data[offset + 16..]));
+        Ok(RtreeQuery {
+            keypath: keypath.to_string(),
+            iid_range: (iid_min, iid_max),
+            bbox: bbox,
+        })
+    }
+}
+
+impl<T: Coordinate> ToMbb for RtreeQuery<T> {
+    fn to_mbb<'a>(&'a self) -> Cow<'a, [u8]> {
+        Cow::Owned(self.to_bytes())
+    }
+
+    fn layout(&self) -> Option<(usize, CoordinateType)> {
+        Some((self.dimensions(), T::coordinate_type()))
+    }
+}
+
+impl ToMbb for [u8] {
+    fn to_mbb<'a>(&'a self) -> Cow<'a, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<'a> ToMbb for &'a [u8] {
+    fn to_mbb<'b>(&'b self) -> Cow<'b, [u8]> {
+        Cow::Borrowed(*self)
+    }
+}
+
+impl ToMbb for Vec<u8> {
+    fn to_mbb<'a>(&'a self) -> Cow<'a, [u8]> {
+        Cow::Borrowed(self.as_slice())
+    }
+}
+
+/// The comparator for R-tree keys, to be used with `Options::set_comparator` together with
+/// `COMPARATOR_NAME` or a name created by `comparator_name`. As all coordinates are stored as
+/// `f64`, it works for every coordinate type.
+///
+/// Keys are ordered by keypath, then by internal id and then by the bounding box values.
+/// Keys that can't be decoded (including the empty key) sort before every valid key and are
+/// compared bytewise among themselves, so the comparator never panics.
+pub fn compare_keys(aa: &[u8], bb: &[u8]) -> Ordering {
+    match (split_key(aa), split_key(bb)) {
+        (Some(parts_aa), Some(parts_bb)) => compare_parts(parts_aa, parts_bb),
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (None, None) => aa.cmp(bb),
+    }
+}
+
+fn compare_parts(aa: (&[u8], u64, &[u8]), bb: (&[u8], u64, &[u8])) -> Ordering {
+    let (keypath_aa, iid_aa, bbox_aa) = aa;
+    let (keypath_bb, iid_bb, bbox_bb) = bb;
+
+    // The ordering of the keypath doesn't need to be unicode collated. The ordering
+    // doesn't really matters, it only matters that it's always the same.
+    let keypath_compare = keypath_aa.cmp(keypath_bb);
+    if keypath_compare != Ordering::Equal {
+        return keypath_compare;
+    }
+
+    // Keypaths are the same, compare the internal ids and then the bounding box
+    let iid_compare = iid_aa.cmp(&iid_bb);
+    if iid_compare != Ordering::Equal {
+        return iid_compare;
+    }
+
+    let values_aa = bbox_aa.chunks(8).map(read_f64);
+    let values_bb = bbox_bb.chunks(8).map(read_f64);
+    for (value_aa, value_bb) in values_aa.zip(values_bb) {
+        // NaNs would make the order inconsistent, fall back to IEEE 754 total ordering
+        let value_compare = value_aa.partial_cmp(&value_bb)
+            .unwrap_or_else(|| value_aa.total_cmp(&value_bb));
+        if value_compare != Ordering::Equal {
+            return value_compare;
+        }
+    }
+    // No early return, the values are equal as far as both keys go
+    bbox_aa.len().cmp(&bbox_bb.len())
+}
+
+fn encode_keypath(out: &mut Vec<u8>, keypath: &str) {
+    let mut len = keypath.len() as u64;
+    while len >= 0x80 {
+        out.push((len as u8) | 0x80);
+        len >>= 7;
+    }
+    out.push(len as u8);
+    out.extend_from_slice(keypath.as_bytes());
+}
+
+fn encode_f64(out: &mut Vec<u8>, value: f64) {
+    out.extend_from_slice(&value.to_bits().to_le_bytes());
+}
+
+fn invalid(message: String) -> Error {
+    Error::with_kind(ErrorKind::InvalidArgument, message)
+}
+
+/// Returns the keypath and the offset right after it.
+fn decode_keypath(data: &[u8]) -> Result<(&str, usize), Error> {
+    let (len, offset) = match decode_varint(data) {
+        Some(decoded) => decoded,
+        None => {
+            return Err(invalid("R-tree keypath length is not a valid varint.".to_owned()))
+        }
+    };
+
+    let end = match keypath_end(data, len, offset) {
+        Some(end) => end,
+        None => return Err(invalid("R-tree key is truncated within the keypath.".to_owned())),
+    };
+    match str::from_utf8(&data[offset..end]) {
+        Ok(keypath) => Ok((keypath, end)),
+        Err(_) => Err(invalid("R-tree keypath is not valid UTF-8.".to_owned())),
+    }
+}
+
+/// Splits a key into keypath, internal id and bounding box without allocating. Returns `None`
+/// if the key is malformed.
+fn split_key(data: &[u8]) -> Option<(&[u8], u64, &[u8])> {
+    let (len, offset) = match decode_varint(data) {
+        Some(decoded) => decoded,
+        None => return None,
+    };
+    let end = match keypath_end(data, len, offset) {
+        Some(end) => end,
+        None => return None,
+    };
+    if data.len() < end + 8 || (data.len() - end - 8) % 8 != 0 {
+        return None;
+    }
+    Some((&data[offset..end], read_u64(&data[end..]), &data[end + 8..]))
+}
+
+/// Decodes the varint at the start of `data`, returns the value and its encoded length.
+fn decode_varint(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+    for (ii, byte) in data.iter().enumerate().take(10) {
+        let shift = ii as u32 * 7;
+        let bits = (byte & 0x7f) as u64;
+        if shift == 63 && bits > 1 {
+            return None;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Some((value, ii + 1));
+        }
+    }
+    None
+}
+
+fn keypath_end(data: &[u8], len: u64, offset: usize) -> Option<usize> {
+    if len > (data.len() - offset) as u64 {
+        None
+    } else {
+        Some(offset + len as usize)
+    }
+}
+
+fn decode_u64(data: &[u8], offset: usize) -> Result<u64, Error> {
+    if data.len() < offset + 8 {
+        return Err(invalid("R-tree key is truncated within the internal id.".to_owned()));
+    }
+    Ok(read_u64(&data[offset..]))
+}
+
+fn decode_bbox<T: Coordinate>(data: &[u8]) -> Result<Vec<T>, Error> {
+    let values = try!(decode_bbox_values(data));
+    values.into_iter()
+        .map(|value| {
+            T::from_f64(value).ok_or_else(|| invalid_coordinate(value, T::coordinate_type()))
+        })
+        .collect()
+}
+
+fn decode_bbox_values(data: &[u8]) -> Result<Vec<f64>, Error> {
+    if data.len() % 16 != 0 {
+        return Err(invalid(format!("R-tree bounding box has an invalid size of {} bytes.",
+                                   data.len())));
+    }
+    Ok(data.chunks(8).map(read_f64).collect())
+}
+
+fn invalid_coordinate(value: f64, coordinate_type: CoordinateType) -> Error {
+    invalid(format!("R-tree coordinate {} is not a valid {:?} value.",
+                    value,
+                    coordinate_type))
+}
+
+fn read_u64(data: &[u8]) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&data[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn read_f64(data: &[u8]) -> f64 {
+    f64::from_bits(read_u64(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CoordinateType, RtreeKey, RtreeQuery, check_key_coordinates, compare_keys,
+                comparator_layout, comparator_name, key_dimensions};
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_key_roundtrip() {
+        let key = RtreeKey::new("somekeypath", 10, vec![10.75, 11.11, 48.24, 48.50]);
+        let encoded = key.to_bytes();
+        assert_eq!(encoded[0], 11);
+        assert_eq!(&encoded[1..12], b"somekeypath");
+        assert_eq!(&encoded[12..20], &[10, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(RtreeKey::from_bytes(&encoded).unwrap(), key);
+
+        let long_keypath: String = ::std::iter::repeat("a").take(300).collect();
+        let key = RtreeKey::new(&long_keypath, 1, vec![0.0, 1.0]);
+        assert_eq!(RtreeKey::from_bytes(&key.to_bytes()).unwrap(), key);
+    }
+
+    #[test]
+    fn test_key_roundtrip_coordinate_types() {
+        let key = RtreeKey::<f32>::new("a", 3, vec![1.5, 2.5, -3.0, 4.0, 0.0, 0.1]);
+        let encoded = key.to_bytes();
+        assert_eq!(RtreeKey::from_bytes(&encoded).unwrap(), key);
+        assert_eq!(key.dimensions(), 3);
+        assert!(check_key_coordinates(&encoded, CoordinateType::F32).is_ok());
+        assert!(check_key_coordinates(&encoded, CoordinateType::I64).is_err());
+
+        let key = RtreeKey::<i64>::new("a", 3, vec![-(1 << 53), 1 << 53]);
+        let encoded = key.to_bytes();
+        assert_eq!(RtreeKey::from_bytes(&encoded).unwrap(), key);
+        assert_eq!(key.dimensions(), 1);
+        assert!(check_key_coordinates(&encoded, CoordinateType::I64).is_ok());
+
+        // 0.1 isn't an `f32` value, 1.5 isn't an `i64` one
+        let encoded = RtreeKey::new("a", 3, vec![0.1, 1.5]).to_bytes();
+        assert!(RtreeKey::<f32>::from_bytes(&encoded).is_err());
+        assert!(RtreeKey::<i64>::from_bytes(&encoded).is_err());
+        assert!(check_key_coordinates(&encoded, CoordinateType::F32).is_err());
+        assert!(check_key_coordinates(&encoded, CoordinateType::F64).is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_key_i64_out_of_range() {
+        RtreeKey::<i64>::new("a", 3, vec![0, (1 << 53) + 1]).to_bytes();
+    }
+
+    #[test]
+    fn test_query_roundtrip() {
+        let query = RtreeQuery::new("somekeypath", (2, 15), vec![10.0, 11.0, 48.0, 49.0]);
+        assert_eq!(RtreeQuery::from_bytes(&query.to_bytes()).unwrap(), query);
+
+        let query = RtreeQuery::<i64>::new("somekeypath", (2, 15), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(RtreeQuery::from_bytes(&query.to_bytes()).unwrap(), query);
+        assert_eq!(query.dimensions(), 4);
+
+        // Bounds beyond the stored range are rounded
+        let query = RtreeQuery::<i64>::new("a", (2, 15), vec![i64::min_value(), i64::max_value()]);
+        let bbox = RtreeQuery::<f64>::from_bytes(&query.to_bytes()).unwrap().bbox;
+        assert_eq!(bbox, vec![-9223372036854775808.0, 9223372036854775808.0]);
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        let encoded = RtreeKey::new("somekeypath", 10, vec![10.75, 11.11]).to_bytes();
+        assert!(RtreeKey::<f64>::from_bytes(&encoded[..encoded.len() - 1]).is_err());
+        assert!(RtreeKey::<f64>::from_bytes(&encoded[..15]).is_err());
+        assert!(RtreeKey::<f64>::from_bytes(&[20, b'a']).is_err());
+    }
+
+    #[test]
+    fn test_dimensions() {
+        assert_eq!(comparator_name(3, CoordinateType::F64), "noise_rtree_cmp_3d");
+        assert_eq!(comparator_name(1, CoordinateType::I64), "noise_rtree_cmp_1d_i64");
+        for &coordinate_type in &[CoordinateType::F64, CoordinateType::F32, CoordinateType::I64] {
+            assert_eq!(comparator_layout(&comparator_name(3, coordinate_type)),
+                       Some((3, coordinate_type)));
+            assert_eq!(comparator_layout(&comparator_name(12, coordinate_type)),
+                       Some((12, coordinate_type)));
+        }
+        assert_eq!(comparator_layout("noise_rtree_cmp"), None);
+        assert_eq!(comparator_layout("noise_rtree_cmp_0d"), None);
+        assert_eq!(comparator_layout("noise_rtree_cmp_xd"), None);
+        assert_eq!(comparator_layout("noise_rtree_cmp_2d_u8"), None);
+        assert_eq!(comparator_layout("leveldb.BytewiseComparator"), None);
+
+        let key = RtreeKey::new("somekeypath", 10, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(key_dimensions(&key.to_bytes()).unwrap(), 3);
+        assert!(key_dimensions(&key.to_bytes()[..30]).is_err());
+    }
+
+    #[test]
+    fn test_compare_keys() {
+        let aa = RtreeKey::new("a", 10, vec![1.0, 2.0]).to_bytes();
+        let bb = RtreeKey::new("b", 5, vec![0.0, 1.0]).to_bytes();
+        let cc = RtreeKey::new("a", 10, vec![1.0, 3.0]).to_bytes();
+        assert_eq!(compare_keys(&aa, &bb), Ordering::Less);
+        assert_eq!(compare_keys(&aa, &cc), Ordering::Less);
+        assert_eq!(compare_keys(&cc, &aa), Ordering::Greater);
+        assert_eq!(compare_keys(&aa, &aa), Ordering::Equal);
+        assert_eq!(compare_keys(&[], &aa), Ordering::Less);
+    }
+
+    #[test]
+    fn test_compare_malformed_keys() {
+        let key = RtreeKey::new("a", 10, vec![1.0, 2.0]).to_bytes();
+        let truncated = &key[..key.len() - 3];
+        let huge_varint = [0xff; 12];
+        let long_keypath = [20, b'a'];
+        for malformed in &[truncated, &huge_varint[..], &long_keypath[..]] {
+            assert_eq!(compare_keys(malformed, &key), Ordering::Less);
+            assert_eq!(compare_keys(&key, malformed), Ordering::Greater);
+            assert_eq!(compare_keys(malformed, malformed), Ordering::Equal);
+        }
+        assert_eq!(compare_keys(truncated, &huge_varint), truncated.cmp(&huge_varint[..]));
+    }
+
+    #[test]
+    fn test_compare_nan() {
+        let nan = RtreeKey::new("a", 10, vec![::std::f64::NAN, 2.0]).to_bytes();
+        let one = RtreeKey::new("a", 10, vec![1.0, 2.0]).to_bytes();
+        assert_eq!(compare_keys(&nan, &one), Ordering::Greater);
+        assert_eq!(compare_keys(&one, &nan), Ordering::Less);
+        assert_eq!(compare_keys(&nan, &nan), Ordering::Equal);
+    }
+}
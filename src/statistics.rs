@@ -0,0 +1,398 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Parsed statistics of a database, as collected when `Options::enable_statistics` is set.
+//!
+//! # Examples
+//!
+//! ```
+//! use rocksdb::{DB, Options};
+//! use rocksdb::statistics::{HistogramType, Ticker};
+//!
+//! let mut opts = Options::default();
+//! opts.create_if_missing(true);
+//! opts.enable_statistics();
+//! let db = DB::open(&opts, "path/for/rocksdb/storage13").unwrap();
+//! let before = db.statistics().unwrap().unwrap();
+//! db.put(b"my key", b"my value").unwrap();
+//! db.get(b"my key").unwrap();
+//! let after = db.statistics().unwrap().unwrap();
+//!
+//! let diff = after.diff(&before);
+//! assert_eq!(diff.ticker(Ticker::NumberKeysWritten), 1);
+//! assert_eq!(diff.ticker(Ticker::NumberKeysRead), 1);
+//! assert!(diff.histogram(HistogramType::DbGet).is_some());
+//! ```
+
+use {Error, ErrorKind};
+
+use std::collections::BTreeMap;
+use std::collections::btree_map;
+
+/// A counter RocksDB keeps track of, see `include/rocksdb/statistics.h` for details.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Ticker {
+    BlockCacheMiss,
+    BlockCacheHit,
+    BlockCacheAdd,
+    BlockCacheIndexMiss,
+    BlockCacheIndexHit,
+    BlockCacheFilterMiss,
+    BlockCacheFilterHit,
+    BlockCacheDataMiss,
+    BlockCacheDataHit,
+    BlockCacheBytesRead,
+    BlockCacheBytesWrite,
+    BloomFilterUseful,
+    MemtableHit,
+    MemtableMiss,
+    GetHitL0,
+    GetHitL1,
+    GetHitL2AndUp,
+    NumberKeysWritten,
+    NumberKeysRead,
+    NumberKeysUpdated,
+    BytesWritten,
+    BytesRead,
+    NumberDbSeek,
+    NumberDbNext,
+    NumberDbPrev,
+    StallMicros,
+    WalFileSynced,
+    WalFileBytes,
+    CompactReadBytes,
+    CompactWriteBytes,
+    FlushWriteBytes,
+}
+
+impl Ticker {
+    /// Returns the name RocksDB uses for the ticker.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Ticker::BlockCacheMiss => "rocksdb.block.cache.miss",
+            Ticker::BlockCacheHit => "rocksdb.block.cache.hit",
+            Ticker::BlockCacheAdd => "rocksdb.block.cache.add",
+            Ticker::BlockCacheIndexMiss => "rocksdb.block.cache.index.miss",
+            Ticker::BlockCacheIndexHit => "rocksdb.block.cache.index.hit",
+            Ticker::BlockCacheFilterMiss => "rocksdb.block.cache.filter.miss",
+            Ticker::BlockCacheFilterHit => "rocksdb.block.cache.filter.hit",
+            Ticker::BlockCacheDataMiss => "rocksdb.block.cache.data.miss",
+            Ticker::BlockCacheDataHit => "rocksdb.block.cache.data.hit",
+            Ticker::BlockCacheBytesRead => "rocksdb.block.cache.bytes.read",
+            Ticker::BlockCacheBytesWrite => "rocksdb.block.cache.bytes.write",
+            Ticker::BloomFilterUseful => "rocksdb.bloom.filter.useful",
+            Ticker::MemtableHit => "rocksdb.memtable.hit",
+            Ticker::MemtableMiss => "rocksdb.memtable.miss",
+            Ticker::GetHitL0 => "rocksdb.l0.hit",
+            Ticker::GetHitL1 => "rocksdb.l1.hit",
+            Ticker::GetHitL2AndUp => "rocksdb.l2andup.hit",
+            Ticker::NumberKeysWritten => "rocksdb.number.keys.written",
+            Ticker::NumberKeysRead => "rocksdb.number.keys.read",
+            Ticker::NumberKeysUpdated => "rocksdb.number.keys.updated",
+            Ticker::BytesWritten => "rocksdb.bytes.written",
+            Ticker::BytesRead => "rocksdb.bytes.read",
+            Ticker::NumberDbSeek => "rocksdb.number.db.seek",
+            Ticker::NumberDbNext => "rocksdb.number.db.next",
+            Ticker::NumberDbPrev => "rocksdb.number.db.prev",
+            Ticker::StallMicros => "rocksdb.stall.micros",
+            Ticker::WalFileSynced => "rocksdb.wal.synced",
+            Ticker::WalFileBytes => "rocksdb.wal.bytes",
+            Ticker::CompactReadBytes => "rocksdb.compact.read.bytes",
+            Ticker::CompactWriteBytes => "rocksdb.compact.write.bytes",
+            Ticker::FlushWriteBytes => "rocksdb.flush.write.bytes",
+        }
+    }
+}
+
+/// A histogram RocksDB keeps track of, see `include/rocksdb/statistics.h` for details.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HistogramType {
+    DbGet,
+    DbWrite,
+    DbSeek,
+    DbMultiget,
+    CompactionTime,
+    TableSyncMicros,
+    CompactionOutfileSyncMicros,
+    WalFileSyncMicros,
+    ManifestFileSyncMicros,
+    TableOpenIoMicros,
+    ReadBlockGetMicros,
+    WriteRawBlockMicros,
+    StallL0SlowdownCount,
+    StallMemtableCompactionCount,
+    StallL0NumFilesCount,
+    HardRateLimitDelayCount,
+    SoftRateLimitDelayCount,
+    NumFilesInSingleCompaction,
+    BytesPerRead,
+    BytesPerWrite,
+    BytesPerMultiget,
+}
+
+impl HistogramType {
+    /// Returns the name RocksDB uses for the histogram.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            HistogramType::DbGet => "rocksdb.db.get.micros",
+            HistogramType::DbWrite => "rocksdb.db.write.micros",
+            HistogramType::DbSeek => "rocksdb.db.seek.micros",
+            HistogramType::DbMultiget => "rocksdb.db.multiget.micros",
+            HistogramType::CompactionTime => "rocksdb.compaction.times.micros",
+            HistogramType::TableSyncMicros => "rocksdb.table.sync.micros",
+            HistogramType::CompactionOutfileSyncMicros => "rocksdb.compaction.outfile.sync.micros",
+            HistogramType::WalFileSyncMicros => "rocksdb.wal.file.sync.micros",
+            HistogramType::ManifestFileSyncMicros => "rocksdb.manifest.file.sync.micros",
+            HistogramType::TableOpenIoMicros => "rocksdb.table.open.io.micros",
+            HistogramType::ReadBlockGetMicros => "rocksdb.read.block.get.micros",
+            HistogramType::WriteRawBlockMicros => "rocksdb.write.raw.block.micros",
+            HistogramType::StallL0SlowdownCount => "rocksdb.l0.slowdown.count",
+            HistogramType::StallMemtableCompactionCount => "rocksdb.memtable.compaction.count",
+            HistogramType::StallL0NumFilesCount => "rocksdb.num.files.stall.count",
+            HistogramType::HardRateLimitDelayCount => "rocksdb.hard.rate.limit.delay.count",
+            HistogramType::SoftRateLimitDelayCount => "rocksdb.soft.rate.limit.delay.count",
+            HistogramType::NumFilesInSingleCompaction => "rocksdb.numfiles.in.singlecompaction",
+            HistogramType::BytesPerRead => "rocksdb.bytes.per.read",
+            HistogramType::BytesPerWrite => "rocksdb.bytes.per.write",
+            HistogramType::BytesPerMultiget => "rocksdb.bytes.per.multiget",
+        }
+    }
+}
+
+/// The summary of a histogram.
+///
+/// Older RocksDB versions only report the percentiles, hence `count` and `sum` are optional.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub p100: f64,
+    pub count: Option<u64>,
+    pub sum: Option<u64>,
+}
+
+/// The tickers and histograms of a database at a certain point in time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Statistics {
+    tickers: BTreeMap<String, u64>,
+    histograms: BTreeMap<String, Histogram>,
+}
+
+impl Statistics {
+    /// Parses the string returned by `Options::get_statistics`.
+    pub fn parse(stats: &str) -> Result<Statistics, Error> {
+        let mut statistics = Statistics::default();
+        for line in stats.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let mut tokens = line.split_whitespace();
+            let name = tokens.next().unwrap().to_owned();
+            let fields = try!(parse_fields(line, tokens));
+            if fields.len() == 1 && fields[0].0 == "COUNT" {
+                statistics.tickers.insert(name, try!(parse_u64(line, fields[0].1)));
+            } else {
+                let histogram = try!(parse_histogram(line, &fields));
+                statistics.histograms.insert(name, histogram);
+            }
+        }
+        Ok(statistics)
+    }
+
+    /// Returns the value of a ticker, 0 if it wasn't reported.
+    pub fn ticker(&self, ticker: Ticker) -> u64 {
+        self.ticker_by_name(ticker.name()).unwrap_or(0)
+    }
+
+    /// Returns the value of a ticker by its RocksDB name, e.g. for tickers that don't have a
+    /// `Ticker` variant.
+    pub fn ticker_by_name(&self, name: &str) -> Option<u64> {
+        self.tickers.get(name).cloned()
+    }
+
+    /// Returns a histogram, `None` if it wasn't reported.
+    pub fn histogram(&self, histogram: HistogramType) -> Option<&Histogram> {
+        self.histogram_by_name(histogram.name())
+    }
+
+    /// Returns a histogram by its RocksDB name.
+    pub fn histogram_by_name(&self, name: &str) -> Option<&Histogram> {
+        self.histograms.get(name)
+    }
+
+    /// Returns all tickers, ordered by name.
+    pub fn tickers<'a>(&'a self) -> btree_map::Iter<'a, String, u64> {
+        self.tickers.iter()
+    }
+
+    /// Returns all histograms, ordered by name.
+    pub fn histograms<'a>(&'a self) -> btree_map::Iter<'a, String, Histogram> {
+        self.histograms.iter()
+    }
+
+    /// Returns the change since an earlier sample, e.g. to compute rates.
+    ///
+    /// Tickers and the count and sum of histograms are subtracted. Percentiles can't be
+    /// subtracted, hence the ones of this sample are kept.
+    pub fn diff(&self, earlier: &Statistics) -> Statistics {
+        let tickers = self.tickers
+            .iter()
+            .map(|(name, value)| {
+                let earlier_value = earlier.tickers.get(name).cloned().unwrap_or(0);
+                (name.clone(), value.saturating_sub(earlier_value))
+            })
+            .collect();
+        let histograms = self.histograms
+            .iter()
+            .map(|(name, histogram)| {
+                let mut diff = histogram.clone();
+                if let Some(earlier_histogram) = earlier.histograms.get(name) {
+                    diff.count = sub_optional(histogram.count, earlier_histogram.count);
+                    diff.sum = sub_optional(histogram.sum, earlier_histogram.sum);
+                }
+                (name.clone(), diff)
+            })
+            .collect();
+        Statistics {
+            tickers: tickers,
+            histograms: histograms,
+        }
+    }
+}
+
+fn sub_optional(value: Option<u64>, earlier: Option<u64>) -> Option<u64> {
+    match (value, earlier) {
+        (Some(value), Some(earlier)) => Some(value.saturating_sub(earlier)),
+        (value, None) => value,
+        (None, _) => None,
+    }
+}
+
+fn parse_error(line: &str) -> Error {
+    Error::with_kind(ErrorKind::InvalidArgument,
+                     format!("Invalid statistics line: {}", line))
+}
+
+/// Parses the `<key> : <value>` pairs following the name. The older histogram format
+/// `statistics Percentiles :=> 50 : <value> ...` is normalized to the newer `P50 : <value>`.
+fn parse_fields<'a, I>(line: &str, tokens: I) -> Result<Vec<(String, &'a str)>, Error>
+    where I: Iterator<Item = &'a str>
+{
+    let tokens: Vec<&str> = tokens.collect();
+    let tokens = if tokens.starts_with(&["statistics", "Percentiles", ":=>"]) {
+        &tokens[3..]
+    } else {
+        &tokens[..]
+    };
+    if tokens.is_empty() || tokens.len() % 3 != 0 {
+        return Err(parse_error(line));
+    }
+    let mut fields = Vec::new();
+    for field in tokens.chunks(3) {
+        if field[1] != ":" {
+            return Err(parse_error(line));
+        }
+        let key = if field[0].chars().all(|c| c.is_ascii_digit()) {
+            format!("P{}", field[0])
+        } else {
+            field[0].to_owned()
+        };
+        fields.push((key, field[2]));
+    }
+    Ok(fields)
+}
+
+fn parse_u64(line: &str, value: &str) -> Result<u64, Error> {
+    value.parse().map_err(|_| parse_error(line))
+}
+
+fn parse_histogram(line: &str, fields: &[(String, &str)]) -> Result<Histogram, Error> {
+    let mut histogram = Histogram {
+        p50: 0.0,
+        p95: 0.0,
+        p99: 0.0,
+        p100: 0.0,
+        count: None,
+        sum: None,
+    };
+    for &(ref key, value) in fields {
+        match key.as_str() {
+            "P50" => histogram.p50 = try!(value.parse().map_err(|_| parse_error(line))),
+            "P95" => histogram.p95 = try!(value.parse().map_err(|_| parse_error(line))),
+            "P99" => histogram.p99 = try!(value.parse().map_err(|_| parse_error(line))),
+            "P100" => histogram.p100 = try!(value.parse().map_err(|_| parse_error(line))),
+            "COUNT" => histogram.count = Some(try!(parse_u64(line, value))),
+            "SUM" => histogram.sum = Some(try!(parse_u64(line, value))),
+            // Ignore fields newer RocksDB versions might add
+            _ => {}
+        }
+    }
+    Ok(histogram)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HistogramType, Statistics, Ticker};
+
+    const SAMPLE: &'static str = "rocksdb.block.cache.miss COUNT : 5
+rocksdb.block.cache.hit COUNT : 12
+rocksdb.bytes.written COUNT : 1024
+rocksdb.db.get.micros P50 : 1.500000 P95 : 3.000000 P99 : 7.250000 P100 : 9.000000 COUNT : 10 SUM : 25
+rocksdb.db.write.micros statistics Percentiles :=> 50 : 2.000000 95 : 4.000000 99 : 5.000000 100 : 6.000000
+";
+
+    #[test]
+    fn test_parse() {
+        let stats = Statistics::parse(SAMPLE).unwrap();
+        assert_eq!(stats.ticker(Ticker::BlockCacheMiss), 5);
+        assert_eq!(stats.ticker(Ticker::BlockCacheHit), 12);
+        assert_eq!(stats.ticker(Ticker::BytesWritten), 1024);
+        assert_eq!(stats.ticker(Ticker::BytesRead), 0);
+        assert_eq!(stats.ticker_by_name("rocksdb.bytes.read"), None);
+        assert_eq!(stats.tickers().count(), 3);
+
+        let get = stats.histogram(HistogramType::DbGet).unwrap();
+        assert_eq!(get.p50, 1.5);
+        assert_eq!(get.p99, 7.25);
+        assert_eq!(get.count, Some(10));
+        assert_eq!(get.sum, Some(25));
+
+        let write = stats.histogram(HistogramType::DbWrite).unwrap();
+        assert_eq!(write.p95, 4.0);
+        assert_eq!(write.p100, 6.0);
+        assert_eq!(write.count, None);
+        assert!(stats.histogram(HistogramType::DbSeek).is_none());
+
+        assert!(Statistics::parse("rocksdb.block.cache.miss COUNT : many").is_err());
+        assert!(Statistics::parse("rocksdb.block.cache.miss").is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let earlier = Statistics::parse(SAMPLE).unwrap();
+        let later = Statistics::parse("rocksdb.block.cache.miss COUNT : 8
+rocksdb.block.cache.hit COUNT : 12
+rocksdb.bytes.read COUNT : 100
+rocksdb.db.get.micros P50 : 2.000000 P95 : 3.000000 P99 : 8.000000 P100 : 9.000000 COUNT : 14 SUM : 40
+")
+            .unwrap();
+        let diff = later.diff(&earlier);
+        assert_eq!(diff.ticker(Ticker::BlockCacheMiss), 3);
+        assert_eq!(diff.ticker(Ticker::BlockCacheHit), 0);
+        assert_eq!(diff.ticker(Ticker::BytesRead), 100);
+        let get = diff.histogram(HistogramType::DbGet).unwrap();
+        assert_eq!(get.count, Some(4));
+        assert_eq!(get.sum, Some(15));
+        assert_eq!(get.p50, 2.0);
+    }
+}
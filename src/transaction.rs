@@ -0,0 +1,827 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Transactions with pessimistic or optimistic concurrency control.
+//!
+//! A `TransactionDB` locks every key a transaction writes or reads with
+//! `Transaction::get_for_update` until the transaction is committed or rolled back. A
+//! transaction that can't acquire a lock in time fails with an error of kind
+//! `ErrorKind::TimedOut` and subcode `ErrorSubCode::LockTimeout`.
+//!
+//! An `OptimisticTransactionDB` doesn't lock anything. Instead committing a transaction fails
+//! with an error of kind `ErrorKind::Busy` if someone else wrote one of the keys it wrote or
+//! read with `Transaction::get_for_update` in the meantime.
+//!
+//! # Examples
+//!
+//! ```
+//! use rocksdb::Options;
+//! use rocksdb::transaction::{TransactionDB, TransactionDBOptions};
+//!
+//! let mut opts = Options::default();
+//! opts.create_if_missing(true);
+//! let db = TransactionDB::open(&opts,
+//!                              &TransactionDBOptions::default(),
+//!                              "path/for/rocksdb/storage19")
+//!     .unwrap();
+//! db.put(b"counter", b"0").unwrap();
+//!
+//! let txn = db.transaction();
+//! let value = txn.get_for_update(b"counter", true).unwrap().unwrap();
+//! let counter: u64 = value.to_utf8().unwrap().parse().unwrap();
+//! txn.put(b"counter", (counter + 1).to_string().as_bytes()).unwrap();
+//! txn.commit().unwrap();
+//!
+//! assert_eq!(&*db.get(b"counter").unwrap().unwrap(), b"1");
+//! ```
+
+use {ColumnFamily, ColumnFamilyState, DB, DBVector, Error, Options, ReadOptions, Snapshot,
+     WriteOptions};
+use db::{OpenArgs, null_readopts_error, open_cf_raw, open_optimistic_transaction_db,
+         to_db_vector};
+use ffi;
+
+use libc::{c_char, c_uchar, int64_t, size_t};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+/// A RocksDB database that supports transactions with pessimistic locking.
+///
+/// Writes outside of a transaction also take the locks of the keys they write, hence they
+/// wait for transactions that hold them.
+pub struct TransactionDB {
+    inner: *mut ffi::rocksdb_transactiondb_t,
+    cfs: BTreeMap<String, Box<ColumnFamilyState>>,
+    path: PathBuf,
+}
+
+unsafe impl Send for TransactionDB {}
+unsafe impl Sync for TransactionDB {}
+
+/// Options of a `TransactionDB` concerning the locking of keys.
+pub struct TransactionDBOptions {
+    inner: *mut ffi::rocksdb_transactiondb_options_t,
+}
+
+/// Options of a single transaction.
+pub struct TransactionOptions {
+    inner: *mut ffi::rocksdb_transaction_options_t,
+}
+
+/// A RocksDB database that supports transactions with optimistic concurrency control.
+///
+/// It dereferences to the underlying `DB`, which can be used as usual, e.g. to take a
+/// `Snapshot`. Its writes are taken into account when transactions check for conflicts.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{ErrorKind, Options};
+/// use rocksdb::transaction::OptimisticTransactionDB;
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = OptimisticTransactionDB::open(&opts, "path/for/rocksdb/storage20").unwrap();
+///
+/// let txn = db.transaction();
+/// assert!(txn.get_for_update(b"my key", true).unwrap().is_none());
+/// txn.put(b"my key", b"my value").unwrap();
+///
+/// // A conflicting write of someone else makes the commit fail
+/// db.put(b"my key", b"another value").unwrap();
+/// assert_eq!(txn.commit().unwrap_err().kind(), ErrorKind::Busy);
+/// assert_eq!(&*db.get(b"my key").unwrap().unwrap(), b"another value");
+/// ```
+pub struct OptimisticTransactionDB {
+    db: DB,
+}
+
+/// Options of a single transaction of an `OptimisticTransactionDB`.
+pub struct OptimisticTransactionOptions {
+    inner: *mut ffi::rocksdb_optimistictransaction_options_t,
+}
+
+/// A transaction of a `TransactionDB` or an `OptimisticTransactionDB`.
+///
+/// Its writes are only visible to others once it is committed. A transaction that is dropped
+/// without being committed is rolled back.
+pub struct Transaction<'a> {
+    inner: *mut ffi::rocksdb_transaction_t,
+    // The transaction must not outlive the database it was begun on. This is the base
+    // database of an `OptimisticTransactionDB`, it's `None` for a `TransactionDB`.
+    db: Option<&'a DB>,
+}
+
+impl TransactionDB {
+    /// Open a transaction database with the default column family only.
+    pub fn open<P: AsRef<Path>>(opts: &Options,
+                                txn_db_opts: &TransactionDBOptions,
+                                path: P)
+                                -> Result<TransactionDB, Error> {
+        TransactionDB::open_cf(opts, txn_db_opts, path, &[], &[])
+    }
+
+    /// Open a transaction database with the given column families, like `DB::open_cf`.
+    pub fn open_cf<P: AsRef<Path>>(opts: &Options,
+                                   txn_db_opts: &TransactionDBOptions,
+                                   path: P,
+                                   cfs: &[&str],
+                                   cf_opts: &[&Options])
+                                   -> Result<TransactionDB, Error> {
+        let path = path.as_ref();
+        let open = |args: &OpenArgs| unsafe {
+            Ok(ffi_try!(ffi::rocksdb_transactiondb_open_column_families(opts.inner,
+                                                                        txn_db_opts.inner,
+                                                                        args.path,
+                                                                        args.num_cfs,
+                                                                        args.cf_names,
+                                                                        args.cf_opts,
+                                                                        args.cf_handles)))
+        };
+        let (db, cf_map) = try!(open_cf_raw(opts,
+                                            path,
+                                            cfs,
+                                            cf_opts,
+                                            true,
+                                            open,
+                                            ffi::rocksdb_transactiondb_close));
+
+        Ok(TransactionDB {
+            inner: db,
+            cfs: cf_map,
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path.as_path()
+    }
+
+    /// Return the underlying column family handle.
+    pub fn cf_handle<'a>(&'a self, name: &str) -> Option<ColumnFamily<'a>> {
+        self.cfs.get(name).map(|state| ColumnFamily { state: state })
+    }
+
+    /// Begins a transaction with the default options.
+    pub fn transaction<'a>(&'a self) -> Transaction<'a> {
+        self.transaction_opt(&WriteOptions::default(), &TransactionOptions::default())
+    }
+
+    /// Begins a transaction, the write options are used when it is committed.
+    pub fn transaction_opt<'a>(&'a self,
+                               writeopts: &WriteOptions,
+                               txn_opts: &TransactionOptions)
+                               -> Transaction<'a> {
+        let inner = unsafe {
+            ffi::rocksdb_transaction_begin(self.inner,
+                                           writeopts.inner,
+                                           txn_opts.inner,
+                                           ptr::null_mut())
+        };
+        Transaction::new(inner, None)
+    }
+
+    pub fn get_opt(&self, key: &[u8], readopts: &ReadOptions) -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(null_readopts_error());
+        }
+        try!(readopts.check_snapshot());
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_transactiondb_get(self.inner,
+                                                              readopts.inner,
+                                                              key.as_ptr() as *const c_char,
+                                                              key.len() as size_t,
+                                                              &mut val_len)) as *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    /// Return the bytes associated with a key value
+    pub fn get(&self, key: &[u8]) -> Result<Option<DBVector>, Error> {
+        self.get_opt(key, &ReadOptions::default())
+    }
+
+    pub fn get_cf_opt(&self,
+                      cf: ColumnFamily,
+                      key: &[u8],
+                      readopts: &ReadOptions)
+                      -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(null_readopts_error());
+        }
+        try!(readopts.check_snapshot());
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_transactiondb_get_cf(self.inner,
+                                                                 readopts.inner,
+                                                                 try!(cf.handle()),
+                                                                 key.as_ptr() as *const c_char,
+                                                                 key.len() as size_t,
+                                                                 &mut val_len)) as *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    pub fn get_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<Option<DBVector>, Error> {
+        self.get_cf_opt(cf, key, &ReadOptions::default())
+    }
+
+    pub fn put_opt(&self, key: &[u8], value: &[u8], writeopts: &WriteOptions) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transactiondb_put(self.inner,
+                                                    writeopts.inner,
+                                                    key.as_ptr() as *const c_char,
+                                                    key.len() as size_t,
+                                                    value.as_ptr() as *const c_char,
+                                                    value.len() as size_t));
+            Ok(())
+        }
+    }
+
+    pub fn put_cf_opt(&self,
+                      cf: ColumnFamily,
+                      key: &[u8],
+                      value: &[u8],
+                      writeopts: &WriteOptions)
+                      -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi_try!(ffi::rocksdb_transactiondb_put_cf(self.inner,
+                                                       writeopts.inner,
+                                                       try!(cf.handle()),
+                                                       key.as_ptr() as *const c_char,
+                                                       key.len() as size_t,
+                                                       value.as_ptr() as *const c_char,
+                                                       value.len() as size_t));
+            Ok(())
+        }
+    }
+
+    pub fn merge_opt(&self,
+                     key: &[u8],
+                     value: &[u8],
+                     writeopts: &WriteOptions)
+                     -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transactiondb_merge(self.inner,
+                                                      writeopts.inner,
+                                                      key.as_ptr() as *const c_char,
+                                                      key.len() as size_t,
+                                                      value.as_ptr() as *const c_char,
+                                                      value.len() as size_t));
+            Ok(())
+        }
+    }
+
+    pub fn delete_opt(&self, key: &[u8], writeopts: &WriteOptions) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transactiondb_delete(self.inner,
+                                                       writeopts.inner,
+                                                       key.as_ptr() as *const c_char,
+                                                       key.len() as size_t));
+            Ok(())
+        }
+    }
+
+    pub fn delete_cf_opt(&self,
+                         cf: ColumnFamily,
+                         key: &[u8],
+                         writeopts: &WriteOptions)
+                         -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi_try!(ffi::rocksdb_transactiondb_delete_cf(self.inner,
+                                                          writeopts.inner,
+                                                          try!(cf.handle()),
+                                                          key.as_ptr() as *const c_char,
+                                                          key.len() as size_t));
+            Ok(())
+        }
+    }
+
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.put_opt(key, value, &WriteOptions::default())
+    }
+
+    pub fn put_cf(&self, cf: ColumnFamily, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.put_cf_opt(cf, key, value, &WriteOptions::default())
+    }
+
+    pub fn merge(&self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.merge_opt(key, value, &WriteOptions::default())
+    }
+
+    pub fn delete(&self, key: &[u8]) -> Result<(), Error> {
+        self.delete_opt(key, &WriteOptions::default())
+    }
+
+    pub fn delete_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<(), Error> {
+        self.delete_cf_opt(cf, key, &WriteOptions::default())
+    }
+}
+
+impl Drop for TransactionDB {
+    fn drop(&mut self) {
+        unsafe {
+            for cf in self.cfs.values() {
+                ffi::rocksdb_column_family_handle_destroy(cf.inner);
+            }
+            ffi::rocksdb_transactiondb_close(self.inner);
+        }
+    }
+}
+
+impl fmt::Debug for TransactionDB {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RocksDB TransactionDB {{ path: {:?} }}", self.path())
+    }
+}
+
+impl OptimisticTransactionDB {
+    /// Open an optimistic transaction database with the default column family only.
+    pub fn open<P: AsRef<Path>>(opts: &Options, path: P) -> Result<OptimisticTransactionDB, Error> {
+        OptimisticTransactionDB::open_cf(opts, path, &[], &[])
+    }
+
+    /// Open an optimistic transaction database with the given column families, like
+    /// `DB::open_cf`.
+    pub fn open_cf<P: AsRef<Path>>(opts: &Options,
+                                   path: P,
+                                   cfs: &[&str],
+                                   cf_opts: &[&Options])
+                                   -> Result<OptimisticTransactionDB, Error> {
+        let db = try!(open_optimistic_transaction_db(opts, path, cfs, cf_opts));
+        Ok(OptimisticTransactionDB { db: db })
+    }
+
+    /// Begins a transaction with the default options.
+    pub fn transaction<'a>(&'a self) -> Transaction<'a> {
+        self.transaction_opt(&WriteOptions::default(),
+                             &OptimisticTransactionOptions::default())
+    }
+
+    /// Begins a transaction, the write options are used when it is committed.
+    pub fn transaction_opt<'a>(&'a self,
+                               writeopts: &WriteOptions,
+                               txn_opts: &OptimisticTransactionOptions)
+                               -> Transaction<'a> {
+        let inner = unsafe {
+            ffi::rocksdb_optimistictransaction_begin(self.db.optimistic_txn_db,
+                                                     writeopts.inner,
+                                                     txn_opts.inner,
+                                                     ptr::null_mut())
+        };
+        Transaction::new(inner, Some(&self.db))
+    }
+}
+
+impl Deref for OptimisticTransactionDB {
+    type Target = DB;
+
+    fn deref(&self) -> &DB {
+        &self.db
+    }
+}
+
+impl fmt::Debug for OptimisticTransactionDB {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "RocksDB OptimisticTransactionDB {{ path: {:?} }}",
+               self.db.path())
+    }
+}
+
+impl<'a> Transaction<'a> {
+    fn new(inner: *mut ffi::rocksdb_transaction_t, db: Option<&'a DB>) -> Transaction<'a> {
+        if inner.is_null() {
+            panic!("Could not begin RocksDB transaction");
+        }
+        Transaction {
+            inner: inner,
+            db: db,
+        }
+    }
+
+    /// Returns the snapshot the transaction took when it began, see
+    /// `OptimisticTransactionOptions::set_snapshot`. It can be passed to
+    /// `ReadOptions::set_snapshot` to read the state the transaction is checked against.
+    ///
+    /// Returns `None` if the transaction didn't take a snapshot, and for transactions of a
+    /// `TransactionDB`, which isn't a `DB`.
+    pub fn snapshot<'b>(&'b self) -> Option<Snapshot<'b>> {
+        self.db.and_then(|db| {
+            let snapshot = unsafe { ffi::rocksdb_transaction_get_snapshot(self.inner) };
+            if snapshot.is_null() {
+                None
+            } else {
+                Some(Snapshot::from_txn(db, snapshot))
+            }
+        })
+    }
+
+    /// Makes the writes of the transaction visible and releases its locks.
+    ///
+    /// With an `OptimisticTransactionDB` this fails with an error of kind `ErrorKind::Busy`
+    /// if a key the transaction wrote or read with `get_for_update` was written by someone
+    /// else since then.
+    pub fn commit(self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_commit(self.inner));
+        }
+        Ok(())
+    }
+
+    /// Discards the writes of the transaction and releases its locks.
+    pub fn rollback(&self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_rollback(self.inner));
+        }
+        Ok(())
+    }
+
+    /// Records the current state of the transaction, see `Transaction::rollback_to_savepoint`.
+    pub fn set_savepoint(&self) {
+        unsafe {
+            ffi::rocksdb_transaction_set_savepoint(self.inner);
+        }
+    }
+
+    /// Discards the writes since the most recent savepoint and removes it.
+    ///
+    /// Returns an error of kind `ErrorKind::NotFound` if there is no savepoint. Locks that
+    /// were acquired since the savepoint are kept until the transaction ends.
+    pub fn rollback_to_savepoint(&self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_rollback_to_savepoint(self.inner));
+        }
+        Ok(())
+    }
+
+    /// Reads a key, including the uncommitted writes of the transaction. The key isn't locked.
+    pub fn get_opt(&self, key: &[u8], readopts: &ReadOptions) -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(null_readopts_error());
+        }
+        try!(readopts.check_snapshot());
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_transaction_get(self.inner,
+                                                            readopts.inner,
+                                                            key.as_ptr() as *const c_char,
+                                                            key.len() as size_t,
+                                                            &mut val_len)) as *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    pub fn get(&self, key: &[u8]) -> Result<Option<DBVector>, Error> {
+        self.get_opt(key, &ReadOptions::default())
+    }
+
+    pub fn get_cf_opt(&self,
+                      cf: ColumnFamily,
+                      key: &[u8],
+                      readopts: &ReadOptions)
+                      -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(null_readopts_error());
+        }
+        try!(readopts.check_snapshot());
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_transaction_get_cf(self.inner,
+                                                               readopts.inner,
+                                                               try!(cf.handle()),
+                                                               key.as_ptr() as *const c_char,
+                                                               key.len() as size_t,
+                                                               &mut val_len)) as *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    pub fn get_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<Option<DBVector>, Error> {
+        self.get_cf_opt(cf, key, &ReadOptions::default())
+    }
+
+    /// Reads a key and locks it until the transaction ends, so that no one else can write it
+    /// in the meantime.
+    ///
+    /// An exclusive lock also keeps others from locking the key with `get_for_update`,
+    /// otherwise the lock is shared with them. With an `OptimisticTransactionDB` nothing is
+    /// locked, but committing the transaction fails if someone else wrote the key since
+    /// then.
+    pub fn get_for_update_opt(&self,
+                              key: &[u8],
+                              exclusive: bool,
+                              readopts: &ReadOptions)
+                              -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(null_readopts_error());
+        }
+        try!(readopts.check_snapshot());
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_transaction_get_for_update(self.inner,
+                                                                       readopts.inner,
+                                                                       key.as_ptr() as
+                                                                       *const c_char,
+                                                                       key.len() as size_t,
+                                                                       &mut val_len,
+                                                                       exclusive as c_uchar)) as
+                      *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    pub fn get_for_update(&self, key: &[u8], exclusive: bool) -> Result<Option<DBVector>, Error> {
+        self.get_for_update_opt(key, exclusive, &ReadOptions::default())
+    }
+
+    pub fn get_for_update_cf_opt(&self,
+                                 cf: ColumnFamily,
+                                 key: &[u8],
+                                 exclusive: bool,
+                                 readopts: &ReadOptions)
+                                 -> Result<Option<DBVector>, Error> {
+        if readopts.inner.is_null() {
+            return Err(null_readopts_error());
+        }
+        try!(readopts.check_snapshot());
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let val = ffi_try!(ffi::rocksdb_transaction_get_for_update_cf(self.inner,
+                                                                          readopts.inner,
+                                                                          try!(cf.handle()),
+                                                                          key.as_ptr() as
+                                                                          *const c_char,
+                                                                          key.len() as size_t,
+                                                                          &mut val_len,
+                                                                          exclusive as
+                                                                          c_uchar)) as
+                      *mut u8;
+            Ok(to_db_vector(val, val_len))
+        }
+    }
+
+    pub fn get_for_update_cf(&self,
+                             cf: ColumnFamily,
+                             key: &[u8],
+                             exclusive: bool)
+                             -> Result<Option<DBVector>, Error> {
+        self.get_for_update_cf_opt(cf, key, exclusive, &ReadOptions::default())
+    }
+
+    /// Writes a key within the transaction and locks it until the transaction ends.
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_put(self.inner,
+                                                  key.as_ptr() as *const c_char,
+                                                  key.len() as size_t,
+                                                  value.as_ptr() as *const c_char,
+                                                  value.len() as size_t));
+            Ok(())
+        }
+    }
+
+    pub fn put_cf(&self, cf: ColumnFamily, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_put_cf(self.inner,
+                                                     try!(cf.handle()),
+                                                     key.as_ptr() as *const c_char,
+                                                     key.len() as size_t,
+                                                     value.as_ptr() as *const c_char,
+                                                     value.len() as size_t));
+            Ok(())
+        }
+    }
+
+    pub fn merge(&self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_merge(self.inner,
+                                                    key.as_ptr() as *const c_char,
+                                                    key.len() as size_t,
+                                                    value.as_ptr() as *const c_char,
+                                                    value.len() as size_t));
+            Ok(())
+        }
+    }
+
+    /// Deletes a key within the transaction and locks it until the transaction ends.
+    pub fn delete(&self, key: &[u8]) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_delete(self.inner,
+                                                     key.as_ptr() as *const c_char,
+                                                     key.len() as size_t));
+            Ok(())
+        }
+    }
+
+    pub fn delete_cf(&self, cf: ColumnFamily, key: &[u8]) -> Result<(), Error> {
+        try!(cf.check_rtree_key(key));
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_delete_cf(self.inner,
+                                                        try!(cf.handle()),
+                                                        key.as_ptr() as *const c_char,
+                                                        key.len() as size_t));
+            Ok(())
+        }
+    }
+}
+
+impl<'a> Drop for Transaction<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_transaction_destroy(self.inner);
+        }
+    }
+}
+
+impl TransactionDBOptions {
+    /// Sets the maximum number of keys that can be locked at the same time per column
+    /// family. Locking more fails with `ErrorSubCode::LockLimit`.
+    ///
+    /// Default: -1, which means no limit.
+    pub fn set_max_num_locks(&mut self, max_num_locks: i64) {
+        unsafe {
+            ffi::rocksdb_transactiondb_options_set_max_num_locks(self.inner,
+                                                                 max_num_locks as int64_t);
+        }
+    }
+
+    /// Sets the number of sub-tables the locks of a column family are split into, more of
+    /// them reduce the contention.
+    ///
+    /// Default: 16
+    pub fn set_num_stripes(&mut self, num_stripes: usize) {
+        unsafe {
+            ffi::rocksdb_transactiondb_options_set_num_stripes(self.inner, num_stripes as size_t);
+        }
+    }
+
+    /// Sets how many milliseconds a transaction waits for a lock by default, it can be
+    /// overridden with `TransactionOptions::set_lock_timeout`. A negative value means no
+    /// timeout.
+    ///
+    /// Default: 1000
+    pub fn set_transaction_lock_timeout(&mut self, timeout_ms: i64) {
+        unsafe {
+            ffi::rocksdb_transactiondb_options_set_transaction_lock_timeout(self.inner,
+                                                                            timeout_ms as
+                                                                            int64_t);
+        }
+    }
+
+    /// Sets how many milliseconds writes outside of a transaction wait for a lock. A negative
+    /// value means no timeout.
+    ///
+    /// Default: 1000
+    pub fn set_default_lock_timeout(&mut self, timeout_ms: i64) {
+        unsafe {
+            ffi::rocksdb_transactiondb_options_set_default_lock_timeout(self.inner,
+                                                                        timeout_ms as int64_t);
+        }
+    }
+}
+
+impl Default for TransactionDBOptions {
+    fn default() -> TransactionDBOptions {
+        let opts = unsafe { ffi::rocksdb_transactiondb_options_create() };
+        if opts.is_null() {
+            panic!("Could not create RocksDB transaction database options");
+        }
+        TransactionDBOptions { inner: opts }
+    }
+}
+
+impl Drop for TransactionDBOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_transactiondb_options_destroy(self.inner);
+        }
+    }
+}
+
+impl TransactionOptions {
+    pub fn new() -> TransactionOptions {
+        TransactionOptions::default()
+    }
+
+    /// If `true`, the transaction takes a snapshot when it begins. Locking a key that was
+    /// written by someone else since then fails with an error of kind `ErrorKind::Busy`.
+    ///
+    /// Default: false
+    pub fn set_snapshot(&mut self, snapshot: bool) {
+        unsafe {
+            ffi::rocksdb_transaction_options_set_set_snapshot(self.inner, snapshot as c_uchar);
+        }
+    }
+
+    /// Sets how many milliseconds the transaction waits for a lock. A negative value means
+    /// the `TransactionDBOptions::set_transaction_lock_timeout` one is used.
+    ///
+    /// Default: -1
+    pub fn set_lock_timeout(&mut self, timeout_ms: i64) {
+        unsafe {
+            ffi::rocksdb_transaction_options_set_lock_timeout(self.inner, timeout_ms as int64_t);
+        }
+    }
+
+    /// Sets after how many milliseconds the locks of the transaction may be taken over by
+    /// others, committing it afterwards fails with `ErrorKind::Expired`. A negative value
+    /// means the transaction never expires.
+    ///
+    /// Default: -1
+    pub fn set_expiration(&mut self, expiration_ms: i64) {
+        unsafe {
+            ffi::rocksdb_transaction_options_set_expiration(self.inner,
+                                                            expiration_ms as int64_t);
+        }
+    }
+
+    /// Sets the maximum size in bytes of the writes of the transaction, 0 means no limit.
+    ///
+    /// Default: 0
+    pub fn set_max_write_batch_size(&mut self, size: usize) {
+        unsafe {
+            ffi::rocksdb_transaction_options_set_max_write_batch_size(self.inner,
+                                                                      size as size_t);
+        }
+    }
+}
+
+impl Default for TransactionOptions {
+    fn default() -> TransactionOptions {
+        let opts = unsafe { ffi::rocksdb_transaction_options_create() };
+        if opts.is_null() {
+            panic!("Could not create RocksDB transaction options");
+        }
+        TransactionOptions { inner: opts }
+    }
+}
+
+impl Drop for TransactionOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_transaction_options_destroy(self.inner);
+        }
+    }
+}
+
+impl OptimisticTransactionOptions {
+    pub fn new() -> OptimisticTransactionOptions {
+        OptimisticTransactionOptions::default()
+    }
+
+    /// If `true`, the transaction takes a snapshot when it begins, so that committing it
+    /// fails if someone else wrote a key of the transaction since then. Otherwise only writes
+    /// since the key was first written or read by the transaction are conflicts.
+    ///
+    /// Default: false
+    pub fn set_snapshot(&mut self, snapshot: bool) {
+        unsafe {
+            ffi::rocksdb_optimistictransaction_options_set_set_snapshot(self.inner,
+                                                                        snapshot as c_uchar);
+        }
+    }
+}
+
+impl Default for OptimisticTransactionOptions {
+    fn default() -> OptimisticTransactionOptions {
+        let opts = unsafe { ffi::rocksdb_optimistictransaction_options_create() };
+        if opts.is_null() {
+            panic!("Could not create RocksDB optimistic transaction options");
+        }
+        OptimisticTransactionOptions { inner: opts }
+    }
+}
+
+impl Drop for OptimisticTransactionOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_optimistictransaction_options_destroy(self.inner);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, Options};
+use rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
+use std::fs;
+
+#[test]
+pub fn test_backup_and_restore() {
+    let path = "_rust_rocksdb_backup_test";
+    let backup_path = "_rust_rocksdb_backup_test_backups";
+    let restore_path = "_rust_rocksdb_backup_test_restore";
+    {
+        let mut backup_engine = BackupEngine::open(&BackupEngineOptions::default(),
+                                                   backup_path)
+            .unwrap();
+        assert!(backup_engine.get_backup_info().is_empty());
+
+        {
+            let db = DB::open_default(path).unwrap();
+            db.put(b"k1", b"v1").unwrap();
+            backup_engine.create_new_backup(&db).unwrap();
+            db.put(b"k2", b"v2").unwrap();
+            backup_engine.create_new_backup(&db).unwrap();
+        }
+
+        let info = backup_engine.get_backup_info();
+        assert_eq!(info.len(), 2);
+        assert!(info[0].backup_id < info[1].backup_id);
+        assert!(info[0].timestamp <= info[1].timestamp);
+        for backup in &info {
+            assert!(backup.size > 0);
+            assert!(backup.num_files > 0);
+            backup_engine.verify_backup(backup.backup_id).unwrap();
+        }
+        assert!(backup_engine.verify_backup(info[1].backup_id + 1).is_err());
+
+        // The latest backup has both keys
+        backup_engine.restore_from_latest_backup(restore_path,
+                                                 restore_path,
+                                                 &RestoreOptions::default())
+            .unwrap();
+        {
+            let db = DB::open_default(restore_path).unwrap();
+            assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+            assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"v2");
+        }
+
+        // The first one only the first key
+        backup_engine.restore_from_backup(restore_path,
+                                          restore_path,
+                                          &RestoreOptions::default(),
+                                          info[0].backup_id)
+            .unwrap();
+        {
+            let db = DB::open_default(restore_path).unwrap();
+            assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+            assert!(db.get(b"k2").unwrap().is_none());
+        }
+
+        backup_engine.delete_backup(info[0].backup_id).unwrap();
+        assert_eq!(backup_engine.get_backup_info(), vec![info[1].clone()]);
+        assert!(backup_engine.verify_backup(info[0].backup_id).is_err());
+        let result = backup_engine.restore_from_backup(restore_path,
+                                                       restore_path,
+                                                       &RestoreOptions::default(),
+                                                       info[0].backup_id);
+        assert!(result.is_err());
+        backup_engine.verify_backup(info[1].backup_id).unwrap();
+    }
+    assert!(DB::destroy(&Options::default(), restore_path).is_ok());
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+    assert!(fs::remove_dir_all(backup_path).is_ok());
+}
+
+#[test]
+pub fn test_purge_old_backups() {
+    let path = "_rust_rocksdb_purge_backups_test";
+    let backup_path = "_rust_rocksdb_purge_backups_test_backups";
+    {
+        let mut backup_engine = BackupEngine::open(&BackupEngineOptions::default(),
+                                                   backup_path)
+            .unwrap();
+        let db = DB::open_default(path).unwrap();
+        for i in 0..3 {
+            db.put(format!("k{}", i).as_bytes(), b"v").unwrap();
+            backup_engine.create_new_backup(&db).unwrap();
+        }
+        let ids: Vec<_> = backup_engine.get_backup_info().iter().map(|b| b.backup_id).collect();
+        assert_eq!(ids.len(), 3);
+
+        backup_engine.purge_old_backups(1).unwrap();
+        let info = backup_engine.get_backup_info();
+        assert_eq!(info.len(), 1);
+        assert_eq!(info[0].backup_id, ids[2]);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+    assert!(fs::remove_dir_all(backup_path).is_ok());
+}
+
+#[test]
+pub fn test_backup_with_metadata() {
+    let path = "_rust_rocksdb_backup_metadata_test";
+    let backup_path = "_rust_rocksdb_backup_metadata_test_backups";
+    {
+        let mut backup_engine = BackupEngine::open(&BackupEngineOptions::default(),
+                                                   backup_path)
+            .unwrap();
+        let db = DB::open_default(path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        backup_engine.create_new_backup(&db).unwrap();
+        backup_engine.create_new_backup_with_metadata(&db, true, "schema_version=2").unwrap();
+        backup_engine.create_new_backup_with_metadata(&db, false, "").unwrap();
+
+        let info = backup_engine.get_backup_info();
+        assert_eq!(info.len(), 3);
+        assert_eq!(info[0].app_metadata, "");
+        assert_eq!(info[1].app_metadata, "schema_version=2");
+        assert_eq!(info[2].app_metadata, "");
+    }
+    {
+        // The metadata is persisted with the backups
+        let backup_engine = BackupEngine::open(&BackupEngineOptions::default(), backup_path)
+            .unwrap();
+        assert_eq!(backup_engine.get_backup_info()[1].app_metadata, "schema_version=2");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+    assert!(fs::remove_dir_all(backup_path).is_ok());
+}
+
+#[test]
+pub fn test_backup_engine_options() {
+    let path = "_rust_rocksdb_backup_options_test";
+    let backup_path = "_rust_rocksdb_backup_options_test_backups";
+    let restore_path = "_rust_rocksdb_backup_options_test_restore";
+    {
+        let mut opts = BackupEngineOptions::default();
+        opts.set_share_table_files(false);
+        opts.set_sync(false);
+        opts.set_backup_log_files(false);
+        opts.set_backup_rate_limit(64 * 1024 * 1024);
+        opts.set_restore_rate_limit(64 * 1024 * 1024);
+        let mut backup_engine = BackupEngine::open(&opts, backup_path).unwrap();
+
+        {
+            let db = DB::open_default(path).unwrap();
+            db.put(b"k1", b"v1").unwrap();
+            // Without the WAL the memtables are flushed anyway
+            backup_engine.create_new_backup_with_metadata(&db, false, "").unwrap();
+        }
+        // Without shared files, each backup has a private directory
+        assert!(fs::metadata(format!("{}/private", backup_path)).is_ok());
+        assert!(fs::read_dir(format!("{}/shared", backup_path))
+            .map(|entries| entries.count() == 0)
+            .unwrap_or(true));
+
+        backup_engine.restore_from_latest_backup(restore_path,
+                                                 restore_path,
+                                                 &RestoreOptions::default())
+            .unwrap();
+        let db = DB::open_default(restore_path).unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+    }
+    {
+        // Opening with destroy_old_data deletes the existing backups
+        let mut opts = BackupEngineOptions::default();
+        opts.set_destroy_old_data(true);
+        let backup_engine = BackupEngine::open(&opts, backup_path).unwrap();
+        assert!(backup_engine.get_backup_info().is_empty());
+    }
+    assert!(DB::destroy(&Options::default(), restore_path).is_ok());
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+    assert!(fs::remove_dir_all(backup_path).is_ok());
+}
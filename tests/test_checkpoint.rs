@@ -0,0 +1,85 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, ColumnFamily, IteratorMode, Options};
+use rocksdb::checkpoint::Checkpoint;
+use std::fs;
+
+fn contents(db: &DB, cf: ColumnFamily) -> Vec<(Box<[u8]>, Box<[u8]>)> {
+    db.iterator_cf(cf, IteratorMode::Start).unwrap().collect()
+}
+
+#[test]
+pub fn test_checkpoint() {
+    let path = "_rust_rocksdb_checkpoint_test";
+    let checkpoint_path = "_rust_rocksdb_checkpoint_test_copy";
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    {
+        let db = DB::open(&opts, path).unwrap();
+        let cf1 = db.create_cf("cf1", &Options::default()).unwrap();
+        let default = db.cf_handle("default").unwrap();
+        for i in 0..100 {
+            db.put(format!("k{:03}", i).as_bytes(), b"v1").unwrap();
+            db.put_cf(cf1, format!("k{:03}", i).as_bytes(), b"v2").unwrap();
+        }
+        db.flush().unwrap();
+        db.delete(b"k000").unwrap();
+        db.put_cf(cf1, b"k100", b"v3").unwrap();
+
+        let checkpoint = Checkpoint::new(&db).unwrap();
+        checkpoint.create_checkpoint(checkpoint_path).unwrap();
+        // The directory must not exist yet
+        assert!(checkpoint.create_checkpoint(checkpoint_path).is_err());
+
+        // Writes after the checkpoint was created are not part of it
+        let expected = contents(&db, default);
+        let expected_cf1 = contents(&db, cf1);
+        db.put(b"k200", b"v4").unwrap();
+
+        // SST files are hard linked
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            let mut sst_files = 0;
+            for entry in fs::read_dir(checkpoint_path).unwrap() {
+                let entry = entry.unwrap();
+                if entry.path().extension().map_or(false, |ext| ext == "sst") {
+                    assert!(entry.metadata().unwrap().nlink() > 1);
+                    sst_files += 1;
+                }
+            }
+            assert!(sst_files > 0);
+        }
+
+        let copy = DB::open_cf(&Options::default(),
+                               checkpoint_path,
+                               &["cf1"],
+                               &[&Options::default()])
+            .unwrap();
+        assert_eq!(contents(&copy, copy.cf_handle("default").unwrap()), expected);
+        assert_eq!(contents(&copy, copy.cf_handle("cf1").unwrap()), expected_cf1);
+        assert!(copy.get(b"k000").unwrap().is_none());
+        assert!(copy.get(b"k200").unwrap().is_none());
+
+        // The checkpoint is independent of the database
+        copy.put(b"k300", b"v5").unwrap();
+        assert!(db.get(b"k300").unwrap().is_none());
+    }
+    assert!(DB::destroy(&Options::default(), checkpoint_path).is_ok());
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
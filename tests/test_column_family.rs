@@ -14,7 +14,9 @@
 //
 extern crate rocksdb;
 
-use rocksdb::{DB, MergeOperands, Options};
+use rocksdb::{DB, ErrorKind, MergeOperands, Options, WriteBatch};
+use std::sync::Arc;
+use std::thread;
 
 #[test]
 pub fn test_column_family() {
@@ -25,7 +27,7 @@ pub fn test_column_family() {
         let mut opts = Options::default();
         opts.create_if_missing(true);
         opts.set_merge_operator("test operator", test_provided_merge);
-        let mut db = DB::open(&opts, path).unwrap();
+        let db = DB::open(&opts, path).unwrap();
         let opts = Options::default();
         match db.create_cf("cf1", &opts) {
             Ok(_) => println!("cf1 created successfully"),
@@ -71,7 +73,7 @@ pub fn test_column_family() {
     // should b able to drop a cf
     {
         let opts = Options::default();
-        let mut db = DB::open_cf(&Options::default(), path, &["cf1"], &[&opts]).unwrap();
+        let db = DB::open_cf(&Options::default(), path, &["cf1"], &[&opts]).unwrap();
         match db.drop_cf("cf1") {
             Ok(_) => println!("cf1 successfully dropped."),
             Err(e) => panic!("failed to drop column family: {}", e),
@@ -81,6 +83,66 @@ pub fn test_column_family() {
     assert!(DB::destroy(&Options::default(), path).is_ok());
 }
 
+#[test]
+pub fn test_dropped_column_family() {
+    let path = "_rust_rocksdb_droppedcftest";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf1 = db.create_cf("cf1", &Options::default()).unwrap();
+        assert_eq!(cf1.name(), "cf1");
+        db.put_cf(cf1, b"k1", b"v1").unwrap();
+
+        db.drop_cf("cf1").unwrap();
+        assert!(db.cf_handle("cf1").is_none());
+        assert!(db.drop_cf("cf1").is_err());
+
+        // The old handle can still be used, but every operation fails cleanly
+        assert!(db.put_cf(cf1, b"k1", b"v1").is_err());
+        assert!(db.get_cf(cf1, b"k1").is_err());
+        assert!(db.iterator_cf(cf1, rocksdb::IteratorMode::Start).is_err());
+        let mut batch = WriteBatch::default();
+        assert!(batch.put_cf(cf1, b"k1", b"v1").is_err());
+
+        // A column family with the same name is a new one
+        let cf1_new = db.create_cf("cf1", &Options::default()).unwrap();
+        assert!(db.get_cf(cf1_new, b"k1").unwrap().is_none());
+        assert!(db.get_cf(cf1, b"k1").is_err());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_concurrent_column_families() {
+    let path = "_rust_rocksdb_concurrentcftest";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = Arc::new(DB::open(&opts, path).unwrap());
+
+        let threads: Vec<_> = (0..4)
+            .map(|ii| {
+                let db = db.clone();
+                thread::spawn(move || {
+                    let name = format!("cf{}", ii);
+                    for _ in 0..10 {
+                        let cf = db.create_cf(&name, &Options::default()).unwrap();
+                        db.put_cf(cf, b"key", b"value").unwrap();
+                        assert!(db.cf_handle(&name).is_some());
+                        db.drop_cf(&name).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert!(db.cf_handle("cf0").is_none());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
 #[test]
 #[ignore]
 fn test_merge_operator() {
@@ -127,6 +189,49 @@ fn test_merge_operator() {
 
 }
 
+#[test]
+fn test_list_and_open_all_cf() {
+    let path = "_rust_rocksdb_open_all_cf_test";
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    // A new database is created with only the default column family
+    {
+        let db = DB::open_all_cf(&opts, path, |_| Options::default()).unwrap();
+        db.create_cf("cf1", &Options::default()).unwrap();
+        db.create_cf("cf2", &Options::default()).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.put_cf(cf1, b"k1", b"v1").unwrap();
+    }
+
+    let mut cfs = DB::list_cf(&Options::default(), path).unwrap();
+    cfs.sort();
+    assert_eq!(cfs, vec!["cf1", "cf2", "default"]);
+
+    // Not naming all existing column families is an error
+    let err = DB::open_cf(&opts, path, &["cf1"], &[&Options::default()]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    let err = DB::open_cf(&opts, path, &["cf\0"], &[&Options::default()]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::CStringConversion);
+
+    {
+        let mut names = Vec::new();
+        let db = DB::open_all_cf(&opts, path, |name| {
+                names.push(name.to_owned());
+                Options::default()
+            })
+            .unwrap();
+        names.sort();
+        assert_eq!(names, vec!["cf1", "cf2", "default"]);
+        let cf1 = db.cf_handle("cf1").unwrap();
+        assert_eq!(&*db.get_cf(cf1, b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.cf_handle("cf2").is_some());
+    }
+
+    assert!(DB::list_cf(&Options::default(), "_rust_rocksdb_no_such_db").is_err());
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
 fn test_provided_merge(_: &[u8],
                        existing_val: Option<&[u8]>,
                        operands: &mut MergeOperands)
@@ -0,0 +1,106 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, ColumnFamily, Options};
+
+fn key(i: usize) -> Vec<u8> {
+    format!("key{:03}", i).into_bytes()
+}
+
+/// Writes the keys `key000` to `key299` into three separate SST files that are not in level 0.
+fn fill(db: &DB, cf: ColumnFamily) {
+    for file in 0..3 {
+        for i in (file * 100)..((file + 1) * 100) {
+            db.put_cf(cf, &key(i), b"value").unwrap();
+        }
+        db.flush_cf(cf).unwrap();
+        db.compact_range_cf(cf,
+                            Some(&key(file * 100)),
+                            Some(&key((file + 1) * 100 - 1)));
+    }
+    let files = db.live_files().unwrap();
+    assert_eq!(files.iter().filter(|file| file.column_family_name == cf.name()).count(),
+               3);
+}
+
+fn existing_keys(db: &DB, cf: ColumnFamily) -> Vec<usize> {
+    (0..300).filter(|&i| db.get_cf(cf, &key(i)).unwrap().is_some()).collect()
+}
+
+#[test]
+pub fn test_delete_file_in_range() {
+    let path = "_rust_rocksdb_delete_file_in_range_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        fill(&db, cf);
+
+        // Only the middle file is fully within the range
+        db.delete_file_in_range_cf(cf, &key(50), &key(250)).unwrap();
+        let expected: Vec<usize> = (0..100).chain(200..300).collect();
+        assert_eq!(existing_keys(&db, cf), expected);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_drop_range() {
+    let path = "_rust_rocksdb_drop_range_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        fill(&db, cf);
+        // Some keys are still in the memtable
+        db.put_cf(cf, b"key150a", b"value").unwrap();
+
+        db.drop_range_cf(cf, &key(50), &key(250)).unwrap();
+        let expected: Vec<usize> = (0..50).chain(250..300).collect();
+        assert_eq!(existing_keys(&db, cf), expected);
+        assert!(db.get_cf(cf, b"key150a").unwrap().is_none());
+
+        // The end is exclusive, even if it is the last key of a file
+        db.drop_range_cf(cf, &key(0), &key(49)).unwrap();
+        let expected: Vec<usize> = (49..50).chain(250..300).collect();
+        assert_eq!(existing_keys(&db, cf), expected);
+
+        // Empty ranges are fine
+        db.drop_range_cf(cf, &key(100), &key(200)).unwrap();
+        assert_eq!(existing_keys(&db, cf), expected);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_drop_range_default_cf() {
+    let path = "_rust_rocksdb_drop_range_default_cf_test";
+    {
+        let db = DB::open_default(path).unwrap();
+        for i in 0..300 {
+            db.put(&key(i), b"value").unwrap();
+        }
+        db.flush().unwrap();
+        db.drop_range(&key(10), &key(290)).unwrap();
+        let existing: Vec<usize> =
+            (0..300).filter(|&i| db.get(&key(i)).unwrap().is_some()).collect();
+        let expected: Vec<usize> = (0..10).chain(290..300).collect();
+        assert_eq!(existing, expected);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
@@ -0,0 +1,93 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, DBIterator, IteratorMode, Options, WriteBatch};
+
+fn keys(iter: DBIterator) -> Vec<Vec<u8>> {
+    iter.map(|(key, _)| key.to_vec()).collect()
+}
+
+#[test]
+pub fn test_delete_range_cf() {
+    let path = "_rust_rocksdb_delete_range_cf_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        for key in &[b"k1", b"k2", b"k3", b"k4", b"k5"] {
+            db.put_cf(cf, *key, b"value").unwrap();
+        }
+        let snapshot = db.snapshot();
+
+        db.delete_range_cf(cf, b"k2", b"k4").unwrap();
+        assert!(db.get_cf(cf, b"k1").unwrap().is_some());
+        assert!(db.get_cf(cf, b"k2").unwrap().is_none());
+        assert!(db.get_cf(cf, b"k3").unwrap().is_none());
+        assert!(db.get_cf(cf, b"k4").unwrap().is_some());
+        assert_eq!(keys(db.iterator_cf(cf, IteratorMode::Start).unwrap()),
+                   vec![b"k1".to_vec(), b"k4".to_vec(), b"k5".to_vec()]);
+        assert_eq!(keys(db.iterator_cf(cf, IteratorMode::End).unwrap()),
+                   vec![b"k5".to_vec(), b"k4".to_vec(), b"k1".to_vec()]);
+
+        // The snapshot was taken before the deletion
+        assert!(snapshot.get_cf(cf, b"k2").unwrap().is_some());
+        assert_eq!(keys(snapshot.iterator_cf(cf, IteratorMode::Start).unwrap()).len(), 5);
+
+        // The tombstone survives a flush
+        db.flush_cf(cf).unwrap();
+        assert!(db.get_cf(cf, b"k3").unwrap().is_none());
+        assert!(snapshot.get_cf(cf, b"k3").unwrap().is_some());
+
+        // Keys written after the deletion are visible
+        db.put_cf(cf, b"k3", b"new value").unwrap();
+        assert_eq!(&*db.get_cf(cf, b"k3").unwrap().unwrap(), b"new value");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_write_batch_delete_range() {
+    let path = "_rust_rocksdb_write_batch_delete_range_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+
+        let mut batch = WriteBatch::default();
+        for key in &[b"k1", b"k2", b"k3"] {
+            batch.put(*key, b"value").unwrap();
+            batch.put_cf(cf, *key, b"value").unwrap();
+        }
+        db.write(batch).unwrap();
+
+        let mut batch = WriteBatch::default();
+        batch.delete_range(b"k1", b"k3").unwrap();
+        batch.delete_range_cf(cf, b"k2", b"k9").unwrap();
+        // Within a batch, later writes win over earlier range deletions
+        batch.put(b"k2", b"new value").unwrap();
+        assert_eq!(batch.len(), 3);
+        db.write(batch).unwrap();
+
+        assert_eq!(keys(db.iterator(IteratorMode::Start)),
+                   vec![b"k2".to_vec(), b"k3".to_vec()]);
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"new value");
+        assert_eq!(keys(db.iterator_cf(cf, IteratorMode::Start).unwrap()),
+                   vec![b"k1".to_vec()]);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
@@ -0,0 +1,50 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, FlushOptions, Options};
+use rocksdb::properties::Property;
+
+#[test]
+pub fn test_flush() {
+    let path = "_rust_rocksdb_flush_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        let files_at_level0 = Property::NumFilesAtLevel(0);
+
+        db.put(b"key1", b"value1").unwrap();
+        assert_eq!(db.property_int_value(&files_at_level0).unwrap(), Some(0));
+        db.flush().unwrap();
+        assert_eq!(db.property_int_value(&files_at_level0).unwrap(), Some(1));
+        assert_eq!(db.property_int_value(&Property::NumEntriesActiveMemTable).unwrap(),
+                   Some(0));
+        assert_eq!(&*db.get(b"key1").unwrap().unwrap(), b"value1");
+
+        db.put_cf(cf, b"key1", b"value1").unwrap();
+        let mut flush_opts = FlushOptions::default();
+        flush_opts.set_wait(true);
+        db.flush_cf_opt(cf, &flush_opts).unwrap();
+        assert_eq!(db.property_int_value_cf(cf, &files_at_level0).unwrap(), Some(1));
+        // Only the column family was flushed
+        assert_eq!(db.property_int_value(&files_at_level0).unwrap(), Some(1));
+
+        db.drop_cf("cf1").unwrap();
+        assert!(db.flush_cf(cf).is_err());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
@@ -0,0 +1,58 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, Options};
+
+#[test]
+pub fn test_live_files() {
+    let path = "_rust_rocksdb_live_files_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        assert!(db.live_files().unwrap().is_empty());
+
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        db.put(b"key1", b"value1").unwrap();
+        db.put(b"key5", b"value5").unwrap();
+        db.flush().unwrap();
+        db.put_cf(cf, b"key2", b"value2").unwrap();
+        db.put_cf(cf, b"key3", b"value3").unwrap();
+        db.flush_cf(cf).unwrap();
+
+        let mut files = db.live_files().unwrap();
+        files.sort_by(|a, b| a.column_family_name.cmp(&b.column_family_name));
+        assert_eq!(files.len(), 2);
+
+        assert_eq!(files[0].column_family_name, "cf1");
+        assert_eq!(files[0].level, 0);
+        assert_eq!(files[0].smallest_key, b"key2");
+        assert_eq!(files[0].largest_key, b"key3");
+        assert!(files[0].name.ends_with(".sst"));
+        assert!(files[0].size > 0);
+
+        assert_eq!(files[1].column_family_name, "default");
+        assert_eq!(files[1].smallest_key, b"key1");
+        assert_eq!(files[1].largest_key, b"key5");
+
+        // A full compaction moves the data to a higher level
+        db.compact_range(None, None);
+        let files = db.live_files().unwrap();
+        let default_file = files.iter().find(|file| file.column_family_name == "default").unwrap();
+        assert!(default_file.level > 0);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
@@ -0,0 +1,109 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, DBVector, Error, Options};
+
+const NUM_KEYS: usize = 10000;
+
+fn key(i: usize) -> Vec<u8> {
+    format!("key{:05}", i).into_bytes()
+}
+
+fn value(result: &Result<Option<DBVector>, Error>) -> Option<Vec<u8>> {
+    result.as_ref().unwrap().as_ref().map(|value| value.to_vec())
+}
+
+fn assert_same(multi: Vec<Result<Option<DBVector>, Error>>,
+               single: Vec<Result<Option<DBVector>, Error>>) {
+    assert_eq!(multi.len(), single.len());
+    for (multi, single) in multi.into_iter().zip(single) {
+        let multi = multi.unwrap().map(|value| value.to_vec());
+        let single = single.unwrap().map(|value| value.to_vec());
+        assert_eq!(multi, single);
+    }
+}
+
+#[test]
+pub fn test_multi_get() {
+    let path = "_rust_rocksdb_multi_get";
+    {
+        let db = DB::open_default(path).unwrap();
+        // Only every other key exists
+        for i in (0..NUM_KEYS).filter(|i| i % 2 == 0) {
+            db.put(&key(i), format!("value{}", i).as_bytes()).unwrap();
+        }
+
+        let keys: Vec<Vec<u8>> = (0..NUM_KEYS).rev().map(key).collect();
+        let multi = db.multi_get(&keys);
+        let single = keys.iter().map(|key| db.get(key)).collect();
+        assert_same(multi, single);
+
+        let multi = db.multi_get(&[b"key00002", b"nokey000", b"key00004"]);
+        assert_eq!(value(&multi[0]), Some(b"value2".to_vec()));
+        assert_eq!(value(&multi[1]), None);
+        assert_eq!(value(&multi[2]), Some(b"value4".to_vec()));
+
+        let empty: Vec<&[u8]> = Vec::new();
+        assert!(db.multi_get(&empty).is_empty());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_multi_get_cf() {
+    let path = "_rust_rocksdb_multi_get_cf";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        for i in (0..NUM_KEYS).filter(|i| i % 3 == 0) {
+            db.put_cf(cf, &key(i), format!("value{}", i).as_bytes()).unwrap();
+        }
+        // Keys in the default column family must not show up
+        db.put(&key(1), b"default").unwrap();
+
+        let keys: Vec<Vec<u8>> = (0..NUM_KEYS).map(key).collect();
+        let multi = db.multi_get_cf(cf, &keys);
+        let single = keys.iter().map(|key| db.get_cf(cf, key)).collect();
+        assert_same(multi, single);
+        assert_eq!(value(&db.multi_get_cf(cf, &[key(1)])[0]), None);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_snapshot_multi_get() {
+    let path = "_rust_rocksdb_snapshot_multi_get";
+    {
+        let db = DB::open_default(path).unwrap();
+        for i in 0..NUM_KEYS {
+            db.put(&key(i), b"before").unwrap();
+        }
+        let snapshot = db.snapshot();
+        for i in 0..NUM_KEYS {
+            db.put(&key(i), b"after").unwrap();
+        }
+
+        let keys: Vec<Vec<u8>> = (0..NUM_KEYS).map(key).collect();
+        let multi = snapshot.multi_get(&keys);
+        let single = keys.iter().map(|key| snapshot.get(key)).collect();
+        assert_same(multi, single);
+        assert_eq!(value(&snapshot.multi_get(&[key(0)])[0]), Some(b"before".to_vec()));
+        assert_eq!(value(&db.multi_get(&[key(0)])[0]), Some(b"after".to_vec()));
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
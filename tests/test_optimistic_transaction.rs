@@ -0,0 +1,202 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, ErrorKind, Options, ReadOptions, WriteOptions};
+use rocksdb::transaction::{OptimisticTransactionDB, OptimisticTransactionOptions};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+const N: usize = 1_000;
+
+fn open(path: &str) -> OptimisticTransactionDB {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    OptimisticTransactionDB::open(&opts, path).unwrap()
+}
+
+#[test]
+pub fn test_optimistic_transaction_multithreaded() {
+    let path = "_rust_rocksdb_optimistic_transaction_multithreaded_test";
+    {
+        let db = Arc::new(open(path));
+        db.put(b"counter", b"0").unwrap();
+        let barrier = Arc::new(Barrier::new(3));
+
+        let threads: Vec<_> = (0..3)
+            .map(|_| {
+                let db = db.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    let mut increments = 0;
+                    while increments < N {
+                        let txn = db.transaction();
+                        let value = txn.get_for_update(b"counter", true).unwrap().unwrap();
+                        let counter: usize = value.to_utf8().unwrap().parse().unwrap();
+                        txn.put(b"counter", (counter + 1).to_string().as_bytes()).unwrap();
+                        match txn.commit() {
+                            Ok(()) => increments += 1,
+                            Err(e) => assert_eq!(e.kind(), ErrorKind::Busy),
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        // No increment got lost, the conflicting ones were retried
+        let expected = (3 * N).to_string();
+        assert_eq!(&*db.get(b"counter").unwrap().unwrap(), expected.as_bytes());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_optimistic_transaction_conflict() {
+    let path = "_rust_rocksdb_optimistic_transaction_conflict_test";
+    {
+        let db = open(path);
+        db.put(b"k1", b"v1").unwrap();
+
+        let txn1 = db.transaction();
+        let txn2 = db.transaction();
+        assert_eq!(&*txn1.get_for_update(b"k1", true).unwrap().unwrap(), b"v1");
+        assert_eq!(&*txn2.get_for_update(b"k1", true).unwrap().unwrap(), b"v1");
+        // Nothing is locked
+        txn1.put(b"k1", b"v2").unwrap();
+        txn2.put(b"k1", b"v3").unwrap();
+
+        txn1.commit().unwrap();
+        let err = txn2.commit().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Busy);
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v2");
+
+        // Keys read with a plain get are not checked for conflicts
+        let txn = db.transaction();
+        assert_eq!(&*txn.get(b"k1").unwrap().unwrap(), b"v2");
+        txn.put(b"k2", b"v2").unwrap();
+        db.put(b"k1", b"v4").unwrap();
+        txn.commit().unwrap();
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"v2");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_optimistic_transaction_snapshot() {
+    let path = "_rust_rocksdb_optimistic_transaction_snapshot_test";
+    {
+        let db = open(path);
+        db.put(b"k1", b"v1").unwrap();
+
+        // Without a snapshot, only writes after the transaction touched a key conflict
+        let txn = db.transaction();
+        db.put(b"k1", b"v2").unwrap();
+        txn.put(b"k1", b"v3").unwrap();
+        txn.commit().unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v3");
+
+        let mut txn_opts = OptimisticTransactionOptions::default();
+        txn_opts.set_snapshot(true);
+        let txn = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+        db.put(b"k1", b"v4").unwrap();
+        txn.put(b"k1", b"v5").unwrap();
+        assert_eq!(txn.commit().unwrap_err().kind(), ErrorKind::Busy);
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v4");
+
+        // Snapshots of the database don't see transactions committed afterwards
+        let snapshot = db.snapshot();
+        let txn = db.transaction();
+        txn.put(b"k1", b"v6").unwrap();
+        txn.commit().unwrap();
+        assert_eq!(&*snapshot.get(b"k1").unwrap().unwrap(), b"v4");
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v6");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_optimistic_transaction_read_at_snapshot() {
+    let path = "_rust_rocksdb_optimistic_transaction_read_at_snapshot_test";
+    {
+        let db = open(path);
+        db.put(b"k1", b"v1").unwrap();
+
+        // Without `set_snapshot` the transaction has no snapshot
+        assert!(db.transaction().snapshot().is_none());
+
+        let mut txn_opts = OptimisticTransactionOptions::default();
+        txn_opts.set_snapshot(true);
+        let txn = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+        txn.put(b"k2", b"v2").unwrap();
+        db.put(b"k1", b"v3").unwrap();
+
+        let mut readopts = ReadOptions::default();
+        {
+            let snapshot = txn.snapshot().unwrap();
+            readopts.set_snapshot(&snapshot);
+            // The transaction sees its own writes on top of the snapshot
+            assert_eq!(&*txn.get_opt(b"k1", &readopts).unwrap().unwrap(), b"v1");
+            assert_eq!(&*txn.get_opt(b"k2", &readopts).unwrap().unwrap(), b"v2");
+            assert_eq!(&*db.get_opt(b"k1", &readopts).unwrap().unwrap(), b"v1");
+            assert!(db.get_opt(b"k2", &readopts).unwrap().is_none());
+        }
+
+        // Reading at a dropped snapshot fails instead of reading freed memory
+        match txn.get_opt(b"k1", &readopts) {
+            Err(e) => assert_eq!(e.kind(), ErrorKind::InvalidArgument),
+            Ok(_) => panic!("read at a dropped snapshot"),
+        }
+        match db.get_opt(b"k1", &readopts) {
+            Err(e) => assert_eq!(e.kind(), ErrorKind::InvalidArgument),
+            Ok(_) => panic!("read at a dropped snapshot"),
+        }
+        assert_eq!(&*txn.get(b"k1").unwrap().unwrap(), b"v3");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_optimistic_transaction_column_family() {
+    let path = "_rust_rocksdb_optimistic_transaction_cf_test";
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    {
+        let db = OptimisticTransactionDB::open(&opts, path).unwrap();
+        let cf1 = db.create_cf("cf1", &Options::default()).unwrap();
+        let txn = db.transaction();
+        txn.put_cf(cf1, b"k1", b"v1").unwrap();
+        txn.put(b"k1", b"v2").unwrap();
+        txn.commit().unwrap();
+    }
+    {
+        let db = OptimisticTransactionDB::open_cf(&opts, path, &["cf1"], &[&Options::default()])
+            .unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        assert_eq!(&*db.get_cf(cf1, b"k1").unwrap().unwrap(), b"v1");
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v2");
+
+        let txn = db.transaction();
+        txn.get_for_update_cf(cf1, b"k1", true).unwrap();
+        db.delete_cf(cf1, b"k1").unwrap();
+        txn.put_cf(cf1, b"k1", b"v3").unwrap();
+        assert_eq!(txn.commit().unwrap_err().kind(), ErrorKind::Busy);
+        assert!(db.get_cf(cf1, b"k1").unwrap().is_none());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
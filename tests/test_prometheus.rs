@@ -0,0 +1,83 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+#![cfg(feature = "prometheus")]
+
+extern crate rocksdb;
+
+use rocksdb::{DB, Options};
+use rocksdb::prometheus::{self, CfMetrics, DbMetrics};
+use rocksdb::properties::Property;
+use rocksdb::statistics::Statistics;
+
+const STATISTICS: &'static str = "rocksdb.block.cache.miss COUNT : 5
+rocksdb.block.cache.hit COUNT : 12
+rocksdb.bytes.written COUNT : 1024
+rocksdb.db.get.micros P50 : 1.500000 P95 : 3.000000 P99 : 7.250000 P100 : 9.000000 COUNT : 10 SUM : 25
+";
+
+fn cf_metrics(name: &str, num_keys: u64, num_files_at_level: Vec<u64>) -> CfMetrics {
+    CfMetrics {
+        name: name.to_owned(),
+        properties: vec![(Property::EstimateNumKeys, num_keys),
+                         (Property::CurSizeAllMemTables, 2048)],
+        num_files_at_level: num_files_at_level,
+    }
+}
+
+#[test]
+fn test_render_golden() {
+    let metrics = vec![DbMetrics {
+                           name: "main".to_owned(),
+                           statistics: Some(Statistics::parse(STATISTICS).unwrap()),
+                           cfs: vec![cf_metrics("default", 3, vec![1, 0, 2]),
+                                     cf_metrics("cf1", 7, vec![0, 0, 0])],
+                       },
+                       DbMetrics {
+                           name: "with \"quotes\"".to_owned(),
+                           statistics: None,
+                           cfs: vec![cf_metrics("default", 0, vec![0])],
+                       }];
+    assert_eq!(prometheus::render(&metrics),
+               include_str!("golden/prometheus.prom"));
+}
+
+#[test]
+fn test_render_empty() {
+    assert_eq!(prometheus::render(&[]), "");
+}
+
+#[test]
+fn test_export() {
+    let path = "_rust_rocksdb_prometheus_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.enable_statistics();
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        db.put(b"key1", b"value1").unwrap();
+        db.put_cf(cf, b"key1", b"value1").unwrap();
+        db.put_cf(cf, b"key2", b"value2").unwrap();
+
+        let metrics = prometheus::export(&[("main", &db)]).unwrap();
+        assert!(metrics.contains("# TYPE rocksdb_estimate_num_keys gauge\n"));
+        assert!(metrics.contains("rocksdb_estimate_num_keys{db=\"main\",cf=\"default\"} 1\n"));
+        assert!(metrics.contains("rocksdb_estimate_num_keys{db=\"main\",cf=\"cf1\"} 2\n"));
+        assert!(metrics.contains("rocksdb_num_files_at_level{db=\"main\",cf=\"cf1\",level=\"0\"} 0\n"));
+        assert!(metrics.contains("# TYPE rocksdb_number_keys_written_total counter\n"));
+        assert!(metrics.contains("rocksdb_number_keys_written_total{db=\"main\"} 3\n"));
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
@@ -0,0 +1,76 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, ErrorKind, Options};
+use rocksdb::properties::Property;
+
+#[test]
+fn test_property() {
+    let path = "_rust_rocksdb_property_test";
+    {
+        let db = DB::open_default(path).unwrap();
+        db.put(b"key1", b"value1").unwrap();
+        db.put(b"key2", b"value2").unwrap();
+
+        let stats = db.property_value(&Property::Stats).unwrap().unwrap();
+        assert!(stats.contains("Compaction Stats"));
+        // Plain strings work as well
+        assert!(db.property_value("rocksdb.stats").unwrap().is_some());
+        assert_eq!(db.property_value("rocksdb.no-such-property").unwrap(), None);
+
+        assert_eq!(db.property_int_value(&Property::EstimateNumKeys).unwrap(), Some(2));
+        assert_eq!(db.property_int_value(&Property::NumFilesAtLevel(0)).unwrap(), Some(0));
+        assert!(db.property_int_value(&Property::CurSizeAllMemTables).unwrap().unwrap() > 0);
+        assert_eq!(db.property_int_value(&Property::NumSnapshots).unwrap(), Some(0));
+        {
+            let _snapshot = db.snapshot();
+            assert_eq!(db.property_int_value(&Property::NumSnapshots).unwrap(), Some(1));
+        }
+
+        let err = db.property_int_value(&Property::Stats).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+fn test_property_cf() {
+    let path = "_rust_rocksdb_property_cf_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        db.put_cf(cf, b"key1", b"value1").unwrap();
+        db.put_cf(cf, b"key2", b"value2").unwrap();
+        db.put_cf(cf, b"key3", b"value3").unwrap();
+        db.put(b"key1", b"value1").unwrap();
+
+        assert_eq!(db.property_int_value_cf(cf, &Property::EstimateNumKeys).unwrap(),
+                   Some(3));
+        assert_eq!(db.property_int_value(&Property::EstimateNumKeys).unwrap(), Some(1));
+        assert!(db.property_value_cf(cf, &Property::CfStats).unwrap().is_some());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+fn test_property_name() {
+    assert_eq!(Property::EstimateNumKeys.name(), "rocksdb.estimate-num-keys");
+    assert_eq!(Property::NumFilesAtLevel(3).name(), "rocksdb.num-files-at-level3");
+    assert_eq!(Property::Stats.to_string(), "rocksdb.stats");
+    assert_eq!(Property::CurSizeAllMemTables.name(), "rocksdb.cur-size-all-mem-tables");
+}
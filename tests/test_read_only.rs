@@ -0,0 +1,84 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, IteratorMode, Options, ReadOnlyDB};
+use std::path::Path;
+
+#[test]
+pub fn test_read_only() {
+    let path = "_rust_rocksdb_read_only_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put(b"k2", b"v2").unwrap();
+
+        // The database can be opened read-only while the writer still has it open
+        let read_only_db = ReadOnlyDB::open(&Options::default(), path, false).unwrap();
+        assert_eq!(read_only_db.path(), Path::new(path));
+        assert_eq!(&*read_only_db.get(b"k1").unwrap().unwrap(), b"v1");
+        let keys: Vec<Box<[u8]>> =
+            read_only_db.iterator(IteratorMode::Start).map(|(key, _)| key).collect();
+        assert_eq!(keys.len(), 2);
+        let snapshot = read_only_db.snapshot();
+        assert_eq!(&*snapshot.get(b"k2").unwrap().unwrap(), b"v2");
+
+        // Changes of the writer aren't visible
+        db.put(b"k3", b"v3").unwrap();
+        assert!(read_only_db.get(b"k3").unwrap().is_none());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_read_only_cf() {
+    let path = "_rust_rocksdb_read_only_cf_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+        db.put_cf(cf, b"k1", b"v1").unwrap();
+        db.flush_cf(cf).unwrap();
+    }
+    {
+        let read_only_db = ReadOnlyDB::open_cf(&Options::default(),
+                                               path,
+                                               &["cf1"],
+                                               &[&Options::default()],
+                                               false)
+            .unwrap();
+        let cf = read_only_db.cf_handle("cf1").unwrap();
+        assert_eq!(&*read_only_db.get_cf(cf, b"k1").unwrap().unwrap(), b"v1");
+        assert!(read_only_db.get(b"k1").unwrap().is_none());
+        let values = read_only_db.multi_get_cf(cf, &[b"k1", b"k2"]);
+        assert!(values[0].as_ref().unwrap().is_some());
+        assert!(values[1].as_ref().unwrap().is_none());
+        assert_eq!(read_only_db.live_files().unwrap().len(), 1);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_read_only_missing() {
+    let path = "_rust_rocksdb_read_only_missing_test";
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    assert!(ReadOnlyDB::open(&opts, path, false).is_err());
+    // Opening read-only doesn't create the directory
+    assert!(!Path::new(path).exists());
+}
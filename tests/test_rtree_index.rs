@@ -12,28 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 //
-extern crate integer_encoding;
-extern crate libc;
-extern crate librocksdb_sys as ffi;
 extern crate rocksdb;
 
-use std::cmp::Ordering;
-use std::{slice, str};
-use std::mem;
+use rocksdb::{BlockBasedOptions, BlockBasedIndexType, DB, DBIterator, Options, ReadOptions,
+              WriteBatch};
+use rocksdb::rtree::{self, Coordinate, CoordinateType, RtreeKey, RtreeQuery};
 
-use self::integer_encoding::VarInt;
-
-use rocksdb::{BlockBasedOptions, BlockBasedIndexType, DB, DBIterator, Options};
-
-
-/// Return the slice that is prefixed with an unsigned 32-bit varint and the offset after
-/// the slice that was read
-fn get_length_prefixed_slice(data: &[u8]) -> (&[u8], usize) {
-    let (size, slice_start) = u32::decode_var(data);
-    let slice_end = slice_start + size as usize;
-    let slice = &data[slice_start..slice_end];
-    (slice, slice_end)
-}
 
 fn values_from_iter(iter: DBIterator) -> Vec<String>{
     let mut vec = Vec::new();
@@ -45,84 +29,12 @@ fn values_from_iter(iter: DBIterator) -> Vec<String>{
 }
 
 fn serialize_key(keypath: &str, iid: u64 , aa_min: f64, aa_max: f64, bb_min: f64, bb_max: f64) -> Vec<u8> {
-    let mut key = Vec::new();
-    key.append(&mut keypath.len().encode_var_vec());
-    key.extend_from_slice(keypath.as_bytes());
-    // The R-tree stores boxes, hence duplicate the input values
-    key.extend_from_slice(&unsafe{ mem::transmute::<u64, [u8; 8]>(iid) });
-    key.extend_from_slice(&unsafe{ mem::transmute::<f64, [u8; 8]>(aa_min) });
-    key.extend_from_slice(&unsafe{ mem::transmute::<f64, [u8; 8]>(aa_max) });
-    key.extend_from_slice(&unsafe{ mem::transmute::<f64, [u8; 8]>(bb_min) });
-    key.extend_from_slice(&unsafe{ mem::transmute::<f64, [u8; 8]>(bb_max) });
-
-    return key;
+    RtreeKey::new(keypath, iid, vec![aa_min, aa_max, bb_min, bb_max]).to_bytes()
 }
 
 fn serialize_query(keypath: &str, iid_min: u64, iid_max: u64, aa_min: f64, aa_max: f64, bb_min: f64, bb_max: f64)
-    -> Vec<u8> {
-        let mut key = Vec::new();
-        key.append(&mut keypath.len().encode_var_vec());
-        key.extend_from_slice(keypath.as_bytes());
-        key.extend_from_slice(&unsafe{ mem::transmute::<u64, [u8; 8]>(iid_min) });
-        key.extend_from_slice(&unsafe{ mem::transmute::<u64, [u8; 8]>(iid_max) });
-        key.extend_from_slice(&unsafe{ mem::transmute::<f64, [u8; 8]>(aa_min) });
-        key.extend_from_slice(&unsafe{ mem::transmute::<f64, [u8; 8]>(aa_max) });
-        key.extend_from_slice(&unsafe{ mem::transmute::<f64, [u8; 8]>(bb_min) });
-        key.extend_from_slice(&unsafe{ mem::transmute::<f64, [u8; 8]>(bb_max) });
-        return key;
-}
-
-fn compare_keys_rtree(aa: &[u8], bb: &[u8]) -> Ordering {
-    if aa.len() == 0 && bb.len() == 0{
-        return Ordering::Equal;
-    } else if aa.len() == 0 {
-        return Ordering::Less;
-    } else if bb.len() == 0 {
-        return Ordering::Greater;
-    }
-
-    let (keypath_aa, offset_aa) = get_length_prefixed_slice(aa);
-    let (keypath_bb, offset_bb) = get_length_prefixed_slice(bb);
-
-    // The ordering of the keypath doesn't need to be unicode collated. The ordering
-    // doesn't really matters, it only matters that it's always the same.
-    let keypath_compare = keypath_aa.cmp(keypath_bb);
-    if keypath_compare != Ordering::Equal {
-        return keypath_compare;
-    }
-
-    // Keypaths are the same, compare the Internal Ids value
-    let seq_aa = unsafe {
-        let array = *(aa[(offset_aa)..].as_ptr() as *const [_; 8]);
-        mem::transmute::<[u8; 8], u64>(array)
-    };
-    let seq_bb = unsafe {
-        let array = *(bb[(offset_bb)..].as_ptr() as *const [_; 8]);
-        mem::transmute::<[u8; 8], u64>(array)
-    };
-    let seq_compare = seq_aa.cmp(&seq_bb);
-    if seq_compare != Ordering::Equal {
-        return seq_compare;
-    }
-
-    // Internal Ids are the same, compare the bounding box
-    let bbox_aa = unsafe {
-        let array = *(aa[(offset_aa + 8)..].as_ptr() as *const [_; 32]);
-        mem::transmute::<[u8; 32], [f64; 4]>(array)
-    };
-    let bbox_bb = unsafe {
-        let array = *(bb[(offset_bb + 8)..].as_ptr() as *const [_; 32]);
-        mem::transmute::<[u8; 32], [f64; 4]>(array)
-    };
-
-    for (value_aa, value_bb) in bbox_aa.into_iter().zip(bbox_bb.into_iter()) {
-        let value_compare = value_aa.partial_cmp(value_bb).unwrap();
-        if value_compare != Ordering::Equal {
-            return value_compare;
-        }
-    }
-    // No early return, the values are fully equal
-    return Ordering::Equal;
+    -> RtreeQuery {
+        RtreeQuery::new(keypath, (iid_min, iid_max), vec![aa_min, aa_max, bb_min, bb_max])
 }
 
 #[test]
@@ -132,93 +44,71 @@ pub fn test_rtree_index() {
     let mut opts = Options::default();
     opts.create_if_missing(true);
 
-    let mut rtree_opts = Options::default();
-    rtree_opts.set_memtable_skip_list_mbb_rep();
-
-    rtree_opts.create_if_missing(true);
-    let mut block_based_opts = BlockBasedOptions::default();
-    block_based_opts.set_index_type(BlockBasedIndexType::RtreeSearch);
-    block_based_opts.set_flush_block_policy_noise();
-    rtree_opts.set_block_based_table_factory(&block_based_opts);
-    rtree_opts.set_comparator("noise_rtree_cmp", compare_keys_rtree);
-
     let keypath = "somekeypath";
     let otherkeypath = "anotherkeypath";
 
     // Start a new scope, else the database can't be destroyed at the end of the test
     {
-        let mut db = DB::open(&opts, path).unwrap();
-        let rtree = db.create_cf("rtree", &rtree_opts).unwrap();
+        let db = DB::open(&opts, path).unwrap();
+        let rtree = db.create_rtree_cf("rtree", 2).unwrap();
 
         let augsburg_key = serialize_key(keypath, 10, 10.75, 11.11, 48.24, 48.50);
-        let augsburg_key_slice = unsafe {
-            slice::from_raw_parts(augsburg_key.as_ptr() as *const u8, augsburg_key.len())
-        };
-        db.put_cf(rtree, augsburg_key_slice, b"augsburg").unwrap();
+        db.put_cf(rtree, &augsburg_key, b"augsburg").unwrap();
         let alameda_key = serialize_key(keypath, 25, -122.34, -122.22, 37.71, 37.80);
-        let alameda_key_slice = unsafe {
-            slice::from_raw_parts(alameda_key.as_ptr() as *const u8, alameda_key.len())
-        };
-        db.put_cf(rtree, alameda_key_slice, b"alameda").unwrap();
+        db.put_cf(rtree, &alameda_key, b"alameda").unwrap();
 
         {
             let query = serialize_query(keypath, 2, 15, 10.0, 11.0, 48.0, 49.0);
-            let iter = db.rtree_iterator(&query.as_slice());
+            let iter = db.rtree_iterator(&query);
             let result = values_from_iter(iter);
             assert_eq!(vec!["augsburg".to_string()], result);
         }
 
         {
             let query = serialize_query(keypath, 2, 50, -150.0, 0.0, 20.0, 40.0);
-            let iter = db.rtree_iterator(&query.as_slice());
+            let iter = db.rtree_iterator(&query);
             let result = values_from_iter(iter);
             assert_eq!(vec!["alameda".to_string()], result);
         }
 
         {
             let query = serialize_query(keypath, 2, 3, 10.0, 11.0, 0.0, 0.1);
-            let iter = db.rtree_iterator(&query.as_slice());
+            let iter = db.rtree_iterator(&query);
             let result = values_from_iter(iter);
             assert!(result.is_empty());
         }
 
         {
             let query = serialize_query(keypath, 2, 60, -180.0, 180.0, -90.0, 90.0);
-            let iter = db.rtree_iterator(&query.as_slice());
+            let iter = db.rtree_iterator(&query);
             let result = values_from_iter(iter);
             assert_eq!(vec!["augsburg".to_string(), "alameda".to_string()],
             result);
         }
 
         let sydney_key = serialize_key(otherkeypath, 15, 150.26, 151.34, -34.17, -33.36);
-        let sydney_key_slice = unsafe {
-            slice::from_raw_parts(sydney_key.as_ptr() as *const u8, sydney_key.len())
-        };
-        db.put_cf(rtree, sydney_key_slice, b"sydney").unwrap();
+        db.put_cf(rtree, &sydney_key, b"sydney").unwrap();
 
         {
             let query = serialize_query(keypath, 2, 15, 10.0, 11.0, 48.0, 49.0);
-            let iter = db.rtree_iterator(&query.as_slice());
+            let iter = db.rtree_iterator(&query);
             let result = values_from_iter(iter);
             assert_eq!(vec!["augsburg".to_string()], result);
         }
 
         {
             let query = serialize_query(otherkeypath, 1, 100, -180.0, 180.0, -90.0, 90.0);
-            let iter = db.rtree_iterator(&query.as_slice());
+            let iter = db.rtree_iterator(&query);
             let result = values_from_iter(iter);
             assert_eq!(vec!["sydney".to_string()], result);
         }
-    }
 
-    // In order to not only test the MemTable, the database needs to be closed (with ending
-    // the scope) and re-opened it again. This way the MemTable is flushed into an SSTable.
-    {
-        let db = DB::open_cf(&opts, path, &[&"rtree"], &[&rtree_opts]).unwrap();
+        // In order to not only test the MemTable, it is flushed into an SSTable
+        db.flush_cf(rtree).unwrap();
 
         {
             let query = serialize_query(keypath, 2, 15, 10.0, 11.0, 48.0, 49.0);
-            let iter = db.rtree_iterator(&query.as_slice());
+            let iter = db.rtree_iterator(&query);
             let result = values_from_iter(iter);
             assert_eq!(vec!["augsburg".to_string()], result);
         }
@@ -226,7 +116,7 @@ pub fn test_rtree_index() {
         // This one would fail without the right flush block policy
         {
             let query = serialize_query(otherkeypath, 1, 100, -180.0, 180.0, -90.0, 90.0);
-            let iter = db.rtree_iterator(&query.as_slice());
+            let iter = db.rtree_iterator(&query);
             let result = values_from_iter(iter);
             assert_eq!(vec!["sydney".to_string()], result);
         }
@@ -234,3 +124,233 @@ pub fn test_rtree_index() {
 
     assert!(DB::destroy(&opts, path).is_ok());
 }
+
+#[test]
+pub fn test_rtree_incomplete_options() {
+    let path = "_rust_rocksdb_rtreeincompleteoptionstest";
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    // The flush block policy is missing
+    let mut rtree_opts = Options::default();
+    rtree_opts.set_memtable_skip_list_mbb_rep();
+    let mut block_based_opts = BlockBasedOptions::default();
+    block_based_opts.set_index_type(BlockBasedIndexType::RtreeSearch);
+    rtree_opts.set_block_based_table_factory(&block_based_opts);
+    rtree_opts.set_comparator(rtree::COMPARATOR_NAME, rtree::compare_keys);
+
+    {
+        let db = DB::open(&opts, path).unwrap();
+        assert!(db.create_cf("rtree", &rtree_opts).is_err());
+        db.create_rtree_cf("rtree", 2).unwrap();
+    }
+
+    assert!(DB::open_cf(&opts, path, &[&"rtree"], &[&rtree_opts]).is_err());
+    // The on-disk comparator doesn't match the one from the options
+    assert!(DB::open_cf(&opts, path, &[&"rtree"], &[&Options::default()]).is_err());
+    assert!(DB::open_cf(&opts, path, &[&"rtree"], &[&Options::for_rtree(2)]).is_ok());
+
+    assert!(DB::destroy(&opts, path).is_ok());
+}
+
+#[test]
+pub fn test_rtree_dimensions_mismatch() {
+    let path = "_rust_rocksdb_rtreedimensionsmismatchtest";
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    {
+        let db = DB::open(&opts, path).unwrap();
+        let rtree = db.create_rtree_cf("rtree", 2).unwrap();
+        assert_eq!(rtree.rtree_dimensions(), Some(2));
+
+        let key = RtreeKey::new("keypath", 1, vec![10.75, 11.11, 48.24, 48.50]);
+        db.put_cf(rtree, &key.to_bytes(), b"augsburg").unwrap();
+        let key = RtreeKey::new("keypath", 2, vec![10.75, 11.11]);
+        assert!(db.put_cf(rtree, &key.to_bytes(), b"augsburg").is_err());
+        assert!(db.merge_cf(rtree, &key.to_bytes(), b"augsburg").is_err());
+        assert!(db.delete_cf(rtree, &key.to_bytes()).is_err());
+        assert!(db.single_delete_cf(rtree, &key.to_bytes()).is_err());
+        assert!(db.put_cf(rtree, b"not an R-tree key", b"augsburg").is_err());
+        let valid_key = RtreeKey::new("keypath", 3, vec![10.0, 11.0, 48.0, 49.0]);
+        assert!(db.delete_range_cf(rtree, &valid_key.to_bytes(), &key.to_bytes()).is_err());
+
+        let mut batch = WriteBatch::default();
+        assert!(batch.put_cf(rtree, &key.to_bytes(), b"augsburg").is_err());
+        assert!(batch.merge_cf(rtree, &key.to_bytes(), b"augsburg").is_err());
+        assert!(batch.delete_cf(rtree, b"not an R-tree key").is_err());
+        assert!(batch.single_delete_cf(rtree, b"not an R-tree key").is_err());
+        assert!(batch.delete_range_cf(rtree, b"not an R-tree key", &valid_key.to_bytes())
+            .is_err());
+
+        let query = RtreeQuery::new("keypath", (0, 10), vec![10.0, 11.0, 48.0, 49.0, 0.0, 1.0]);
+        assert!(db.rtree_iterator_cf(rtree, &query).is_err());
+        let query = RtreeQuery::<i64>::new("keypath", (0, 10), vec![10, 11, 48, 49]);
+        assert!(db.rtree_iterator_cf(rtree, &query).is_err());
+    }
+
+    // The number of dimensions is part of the comparator name RocksDB stores
+    assert!(DB::open_cf(&opts, path, &[&"rtree"], &[&Options::for_rtree(3)]).is_err());
+    assert!(DB::open_cf(&opts, path, &[&"rtree"], &[&Options::for_rtree(2)]).is_ok());
+
+    assert!(DB::destroy(&opts, path).is_ok());
+}
+
+fn check_dimensions<T: Coordinate>(path: &str, dimensions: usize, values: &[T]) {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let coordinate_type = T::coordinate_type();
+
+    // A box around every value in every dimension, the query only matches the middle one
+    let boxes: Vec<Vec<T>> = values.iter()
+        .map(|value| (0..dimensions).flat_map(|_| vec![*value, *value]).collect())
+        .collect();
+    let query = RtreeQuery::new("keypath", (0, 100), boxes[1].clone());
+
+    {
+        let db = DB::open(&opts, path).unwrap();
+        let rtree = db.create_rtree_cf_with_type("rtree", dimensions, coordinate_type).unwrap();
+        for (iid, bbox) in boxes.into_iter().enumerate() {
+            let key = RtreeKey::new("keypath", iid as u64, bbox);
+            db.put_cf(rtree, &key.to_bytes(), format!("{}", iid).as_bytes()).unwrap();
+        }
+        let result = values_from_iter(db.rtree_iterator(&query));
+        assert_eq!(vec!["1".to_string()], result, "{} dimensions in memtable", dimensions);
+    }
+
+    // Re-open to flush the memtable into an SSTable
+    {
+        let rtree_opts = Options::for_rtree_with_type(dimensions, coordinate_type);
+        let db = DB::open_cf(&opts, path, &[&"rtree"], &[&rtree_opts]).unwrap();
+        let result = values_from_iter(db.rtree_iterator(&query));
+        assert_eq!(vec!["1".to_string()], result, "{} dimensions in SSTable", dimensions);
+    }
+
+    assert!(DB::destroy(&opts, path).is_ok());
+}
+
+#[test]
+pub fn test_rtree_dimensions() {
+    for dimensions in 1..5 {
+        let path = format!("_rust_rocksdb_rtreedimensionstest_{}", dimensions);
+        check_dimensions::<f64>(&path, dimensions, &[-10.5, 0.25, 20.0]);
+    }
+}
+
+#[test]
+pub fn test_rtree_coordinate_types() {
+    check_dimensions::<f32>("_rust_rocksdb_rtreecoordinatetypestest_f32", 2, &[-10.5, 0.25, 20.0]);
+    check_dimensions::<i64>("_rust_rocksdb_rtreecoordinatetypestest_i64",
+                            3,
+                            &[-(1 << 53), 5, 1 << 40]);
+}
+
+#[test]
+pub fn test_rtree_coordinate_type_mismatch() {
+    let path = "_rust_rocksdb_rtreecoordinatetypemismatchtest";
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    {
+        let db = DB::open(&opts, path).unwrap();
+        let rtree = db.create_rtree_cf_with_type("rtree", 1, CoordinateType::I64).unwrap();
+        assert_eq!(rtree.rtree_coordinate_type(), Some(CoordinateType::I64));
+
+        let key = RtreeKey::<i64>::new("keypath", 1, vec![1500, 2000]);
+        db.put_cf(rtree, &key.to_bytes(), b"interval").unwrap();
+        let key = RtreeKey::new("keypath", 2, vec![1500.5, 2000.0]);
+        assert!(db.put_cf(rtree, &key.to_bytes(), b"interval").is_err());
+    }
+
+    // The coordinate type is part of the comparator name RocksDB stores
+    assert!(DB::open_cf(&opts, path, &[&"rtree"], &[&Options::for_rtree(1)]).is_err());
+    let rtree_opts = Options::for_rtree_with_type(1, CoordinateType::I64);
+    assert!(DB::open_cf(&opts, path, &[&"rtree"], &[&rtree_opts]).is_ok());
+
+    assert!(DB::destroy(&opts, path).is_ok());
+}
+
+#[test]
+pub fn test_rtree_iterator_cf() {
+    let path = "_rust_rocksdb_rtreeiteratorcftest";
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let all = serialize_query("keypath", 0, 100, -180.0, 180.0, -90.0, 90.0);
+    {
+        let db = DB::open(&opts, path).unwrap();
+        let cities = db.create_rtree_cf("cities", 2).unwrap();
+        let lakes = db.create_rtree_cf("lakes", 2).unwrap();
+
+        let augsburg = serialize_key("keypath", 10, 10.75, 11.11, 48.24, 48.50);
+        db.put_cf(cities, &augsburg, b"augsburg").unwrap();
+        let alameda = serialize_key("keypath", 25, -122.34, -122.22, 37.71, 37.80);
+        db.put_cf(cities, &alameda, b"alameda").unwrap();
+        let ammersee = serialize_key("keypath", 12, 11.08, 11.17, 47.93, 48.08);
+        db.put_cf(lakes, &ammersee, b"ammersee").unwrap();
+
+        // Every column family only returns its own entries
+        let result = values_from_iter(db.rtree_iterator_cf(cities, &all).unwrap());
+        assert_eq!(vec!["augsburg".to_string(), "alameda".to_string()], result);
+        let result = values_from_iter(db.rtree_iterator_cf(lakes, &all).unwrap());
+        assert_eq!(vec!["ammersee".to_string()], result);
+
+        // Snapshots don't see later changes
+        let snapshot = db.snapshot();
+        let sydney = serialize_key("keypath", 15, 150.26, 151.34, -34.17, -33.36);
+        db.put_cf(cities, &sydney, b"sydney").unwrap();
+        let result = values_from_iter(snapshot.rtree_iterator_cf(cities, &all).unwrap());
+        assert_eq!(vec!["augsburg".to_string(), "alameda".to_string()], result);
+        let result = values_from_iter(db.rtree_iterator_cf(cities, &all).unwrap());
+        assert_eq!(vec!["augsburg".to_string(), "sydney".to_string(), "alameda".to_string()],
+                   result);
+
+        // The read options are respected
+        let upper_bound = serialize_key("keypath", 20, -180.0, -180.0, -90.0, -90.0);
+        let mut readopts = ReadOptions::default();
+        readopts.set_iterate_upper_bound(&upper_bound);
+        readopts.fill_cache(false);
+        let iter = db.rtree_iterator_cf_opt(cities, &all, readopts).unwrap();
+        assert_eq!(vec!["augsburg".to_string(), "sydney".to_string()], values_from_iter(iter));
+
+        let mut readopts = ReadOptions::default();
+        readopts.set_iterate_upper_bound(&upper_bound);
+        let iter = snapshot.rtree_iterator_cf_opt(cities, &all, readopts).unwrap();
+        assert_eq!(vec!["augsburg".to_string()], values_from_iter(iter));
+    }
+
+    assert!(DB::destroy(&opts, path).is_ok());
+}
+
+#[test]
+pub fn test_rtree_iterator_outlives_buffers() {
+    let path = "_rust_rocksdb_rtreeiteratoroutlivesbufferstest";
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    {
+        let db = DB::open(&opts, path).unwrap();
+        let cities = db.create_rtree_cf("cities", 2).unwrap();
+        let augsburg = serialize_key("keypath", 10, 10.75, 11.11, 48.24, 48.50);
+        db.put_cf(cities, &augsburg, b"augsburg").unwrap();
+        let alameda = serialize_key("keypath", 25, -122.34, -122.22, 37.71, 37.80);
+        db.put_cf(cities, &alameda, b"alameda").unwrap();
+
+        // The query and the upper bound are dropped before the iterator is used
+        let iter = {
+            let all = serialize_query("keypath", 0, 100, -180.0, 180.0, -90.0, 90.0);
+            let upper_bound = serialize_key("keypath", 20, -180.0, -180.0, -90.0, -90.0);
+            let mut readopts = ReadOptions::default();
+            readopts.set_iterate_upper_bound(&upper_bound);
+            db.rtree_iterator_cf_opt(cities, &all, readopts).unwrap()
+        };
+        assert_eq!(vec!["augsburg".to_string()], values_from_iter(iter));
+    }
+
+    assert!(DB::destroy(&opts, path).is_ok());
+}
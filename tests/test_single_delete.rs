@@ -0,0 +1,52 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, Options, WriteBatch};
+
+#[test]
+pub fn test_single_delete() {
+    let path = "_rust_rocksdb_single_delete_test";
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).unwrap();
+        let cf = db.create_cf("cf1", &Options::default()).unwrap();
+
+        db.put(b"k1", b"value").unwrap();
+        db.put_cf(cf, b"k1", b"value").unwrap();
+        db.single_delete(b"k1").unwrap();
+        db.single_delete_cf(cf, b"k1").unwrap();
+        assert!(db.get(b"k1").unwrap().is_none());
+        assert!(db.get_cf(cf, b"k1").unwrap().is_none());
+
+        db.flush().unwrap();
+        db.compact_range(None, None);
+        assert!(db.get(b"k1").unwrap().is_none());
+
+        let mut batch = WriteBatch::default();
+        batch.put(b"k2", b"value").unwrap();
+        batch.put_cf(cf, b"k2", b"value").unwrap();
+        db.write(batch).unwrap();
+        let mut batch = WriteBatch::default();
+        batch.single_delete(b"k2").unwrap();
+        batch.single_delete_cf(cf, b"k2").unwrap();
+        assert_eq!(batch.len(), 2);
+        db.write(batch).unwrap();
+        assert!(db.get(b"k2").unwrap().is_none());
+        assert!(db.get_cf(cf, b"k2").unwrap().is_none());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
@@ -0,0 +1,215 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, ErrorKind, ErrorSubCode, Options, WriteOptions};
+use rocksdb::rtree::RtreeKey;
+use rocksdb::transaction::{TransactionDB, TransactionDBOptions, TransactionOptions};
+use std::sync::Arc;
+use std::thread;
+
+fn open(path: &str) -> TransactionDB {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    TransactionDB::open(&opts, &TransactionDBOptions::default(), path).unwrap()
+}
+
+#[test]
+pub fn test_transaction_counter() {
+    let path = "_rust_rocksdb_transaction_counter_test";
+    {
+        let db = Arc::new(open(path));
+        db.put(b"counter", b"0").unwrap();
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let db = db.clone();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        let txn = db.transaction();
+                        let value = txn.get_for_update(b"counter", true).unwrap().unwrap();
+                        let counter: u64 = value.to_utf8().unwrap().parse().unwrap();
+                        txn.put(b"counter", (counter + 1).to_string().as_bytes()).unwrap();
+                        txn.commit().unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(&*db.get(b"counter").unwrap().unwrap(), b"400");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_transaction_lock_timeout() {
+    let path = "_rust_rocksdb_transaction_lock_timeout_test";
+    {
+        let db = open(path);
+        let txn1 = db.transaction();
+        txn1.put(b"k1", b"v1").unwrap();
+
+        let mut txn_opts = TransactionOptions::default();
+        txn_opts.set_lock_timeout(10);
+        let txn2 = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+        let err = txn2.put(b"k1", b"v2").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        assert_eq!(err.subcode(), ErrorSubCode::LockTimeout);
+        let err = txn2.get_for_update(b"k1", false).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+
+        // Other keys are not locked
+        txn2.put(b"k2", b"v2").unwrap();
+        txn2.commit().unwrap();
+
+        // Writes outside of transactions wait for the lock as well
+        let err = db.put(b"k1", b"v3").unwrap_err();
+        assert_eq!(err.subcode(), ErrorSubCode::LockTimeout);
+
+        // The lock is released once the transaction ends
+        txn1.commit().unwrap();
+        db.put(b"k1", b"v3").unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v3");
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"v2");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_transaction_rollback() {
+    let path = "_rust_rocksdb_transaction_rollback_test";
+    {
+        let db = open(path);
+        db.put(b"k1", b"v1").unwrap();
+
+        let txn = db.transaction();
+        txn.put(b"k1", b"v2").unwrap();
+        txn.delete(b"k1").unwrap();
+        assert!(txn.get(b"k1").unwrap().is_none());
+        txn.put(b"k2", b"v2").unwrap();
+        // Uncommitted writes are not visible to others
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.get(b"k2").unwrap().is_none());
+        txn.rollback().unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.get(b"k2").unwrap().is_none());
+
+        // Dropping a transaction rolls it back and releases its locks
+        {
+            let txn = db.transaction();
+            txn.put(b"k1", b"v3").unwrap();
+        }
+        db.put(b"k3", b"v3").unwrap();
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_transaction_savepoint() {
+    let path = "_rust_rocksdb_transaction_savepoint_test";
+    {
+        let db = open(path);
+        let txn = db.transaction();
+        assert_eq!(txn.rollback_to_savepoint().unwrap_err().kind(),
+                   ErrorKind::NotFound);
+
+        txn.put(b"k1", b"v1").unwrap();
+        txn.set_savepoint();
+        txn.put(b"k1", b"v2").unwrap();
+        txn.put(b"k2", b"v2").unwrap();
+        txn.set_savepoint();
+        txn.delete(b"k2").unwrap();
+
+        txn.rollback_to_savepoint().unwrap();
+        assert_eq!(&*txn.get(b"k2").unwrap().unwrap(), b"v2");
+        txn.rollback_to_savepoint().unwrap();
+        assert_eq!(&*txn.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(txn.get(b"k2").unwrap().is_none());
+        assert!(txn.rollback_to_savepoint().is_err());
+        txn.commit().unwrap();
+
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.get(b"k2").unwrap().is_none());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_transaction_column_family() {
+    let path = "_rust_rocksdb_transaction_cf_test";
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    {
+        let db = DB::open(&opts, path).unwrap();
+        db.create_cf("cf1", &Options::default()).unwrap();
+    }
+    {
+        let db = TransactionDB::open_cf(&opts,
+                                        &TransactionDBOptions::default(),
+                                        path,
+                                        &["cf1"],
+                                        &[&Options::default()])
+            .unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        assert!(db.cf_handle("cf2").is_none());
+        db.put_cf(cf1, b"k1", b"v1").unwrap();
+
+        let txn = db.transaction();
+        assert_eq!(&*txn.get_for_update_cf(cf1, b"k1", true).unwrap().unwrap(), b"v1");
+        assert!(txn.get(b"k1").unwrap().is_none());
+        txn.put_cf(cf1, b"k2", b"v2").unwrap();
+        txn.delete_cf(cf1, b"k1").unwrap();
+        txn.commit().unwrap();
+
+        assert!(db.get_cf(cf1, b"k1").unwrap().is_none());
+        assert_eq!(&*db.get_cf(cf1, b"k2").unwrap().unwrap(), b"v2");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_transaction_rtree_keys() {
+    let path = "_rust_rocksdb_transaction_rtree_keys_test";
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    {
+        let db = DB::open(&opts, path).unwrap();
+        db.create_rtree_cf("rtree", 2).unwrap();
+    }
+    {
+        let db = TransactionDB::open_cf(&opts,
+                                        &TransactionDBOptions::default(),
+                                        path,
+                                        &["rtree"],
+                                        &[&Options::for_rtree(2)])
+            .unwrap();
+        let rtree = db.cf_handle("rtree").unwrap();
+        let key = RtreeKey::new("keypath", 1, vec![10.75, 11.11, 48.24, 48.50]);
+        db.put_cf(rtree, &key.to_bytes(), b"augsburg").unwrap();
+        let key = RtreeKey::new("keypath", 2, vec![10.75, 11.11]);
+        assert!(db.put_cf(rtree, &key.to_bytes(), b"augsburg").is_err());
+        assert!(db.delete_cf(rtree, b"not an R-tree key").is_err());
+
+        let txn = db.transaction();
+        assert!(txn.put_cf(rtree, &key.to_bytes(), b"augsburg").is_err());
+        assert!(txn.delete_cf(rtree, b"not an R-tree key").is_err());
+        txn.commit().unwrap();
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
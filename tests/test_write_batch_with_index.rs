@@ -0,0 +1,226 @@
+// Copyright 2014 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+extern crate rocksdb;
+
+use rocksdb::{DB, DBRawIterator, ErrorKind, Options, WriteBatchWithIndex, WriteOptions};
+use rocksdb::rtree::RtreeKey;
+use std::cmp::Ordering;
+
+fn collect(iter: &mut DBRawIterator) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let mut entries = Vec::new();
+    iter.seek_to_first();
+    while iter.valid() {
+        entries.push((iter.key().unwrap(), iter.value().unwrap()));
+        iter.next();
+    }
+    entries
+}
+
+fn entry(key: &[u8], value: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    (key.to_vec(), value.to_vec())
+}
+
+fn reverse_compare(a: &[u8], b: &[u8]) -> Ordering {
+    b.cmp(a)
+}
+
+#[test]
+pub fn test_write_batch_with_index_get() {
+    let path = "_rust_rocksdb_write_batch_with_index_get_test";
+    {
+        let db = DB::open_default(path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put(b"k2", b"v2").unwrap();
+
+        let mut batch = WriteBatchWithIndex::default();
+        assert!(batch.is_empty());
+        batch.put(b"k2", b"v2a").unwrap();
+        batch.put(b"k3", b"v3").unwrap();
+        batch.put(b"k3", b"v3a").unwrap();
+        batch.delete(b"k1").unwrap();
+        batch.merge(b"k4", b"v4").unwrap();
+        assert_eq!(batch.len(), 5);
+
+        // Only the batch
+        assert!(batch.get_from_batch(b"k1").unwrap().is_none());
+        assert_eq!(&*batch.get_from_batch(b"k2").unwrap().unwrap(), b"v2a");
+        assert_eq!(&*batch.get_from_batch(b"k3").unwrap().unwrap(), b"v3a");
+        assert_eq!(batch.get_from_batch(b"k4").err().unwrap().kind(),
+                   ErrorKind::MergeInProgress);
+        assert!(batch.get_from_batch(b"k5").unwrap().is_none());
+
+        // The batch on top of the database
+        assert!(batch.get_from_batch_and_db(&db, b"k1").unwrap().is_none());
+        assert_eq!(&*batch.get_from_batch_and_db(&db, b"k2").unwrap().unwrap(), b"v2a");
+        assert_eq!(&*batch.get_from_batch_and_db(&db, b"k3").unwrap().unwrap(), b"v3a");
+        db.put(b"k5", b"v5").unwrap();
+        assert_eq!(&*batch.get_from_batch_and_db(&db, b"k5").unwrap().unwrap(), b"v5");
+
+        // Nothing is written until the batch is
+        assert_eq!(&*db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.get(b"k3").unwrap().is_none());
+
+        batch.clear();
+        assert!(batch.is_empty());
+        assert!(batch.get_from_batch(b"k2").unwrap().is_none());
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_write_batch_with_index_write() {
+    let path = "_rust_rocksdb_write_batch_with_index_write_test";
+    {
+        let db = DB::open_default(path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+
+        let mut batch = WriteBatchWithIndex::default();
+        batch.delete(b"k1").unwrap();
+        batch.put(b"k2", b"v2").unwrap();
+        let mut write_options = WriteOptions::default();
+        write_options.set_sync(true);
+        db.write_opt(batch, &write_options).unwrap();
+
+        assert!(db.get(b"k1").unwrap().is_none());
+        assert_eq!(&*db.get(b"k2").unwrap().unwrap(), b"v2");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_write_batch_with_index_iterator() {
+    let path = "_rust_rocksdb_write_batch_with_index_iterator_test";
+    {
+        let db = DB::open_default(path).unwrap();
+        db.put(b"a", b"1").unwrap();
+        db.put(b"b", b"2").unwrap();
+        db.put(b"d", b"4").unwrap();
+
+        let mut batch = WriteBatchWithIndex::default();
+        batch.delete(b"a").unwrap();
+        batch.put(b"c", b"3").unwrap();
+        batch.put(b"d", b"4a").unwrap();
+        batch.put(b"e", b"5").unwrap();
+
+        {
+            let mut iter = batch.iterator_with_base(db.raw_iterator()).unwrap();
+            assert_eq!(collect(&mut iter),
+                       vec![entry(b"b", b"2"), entry(b"c", b"3"), entry(b"d", b"4a"),
+                            entry(b"e", b"5")]);
+
+            iter.seek_to_last();
+            assert_eq!(iter.key(), Some(b"e".to_vec()));
+            iter.prev();
+            assert_eq!(iter.key(), Some(b"d".to_vec()));
+            iter.seek(b"a");
+            assert_eq!(iter.key(), Some(b"b".to_vec()));
+        }
+
+        // Over a snapshot, later writes to the database are not visible
+        let snapshot = db.snapshot();
+        db.put(b"f", b"6").unwrap();
+        let mut iter = batch.iterator_with_base(snapshot.raw_iterator()).unwrap();
+        assert_eq!(collect(&mut iter).len(), 4);
+
+        // Without overwrite_key the batch can't be iterated with a base
+        let batch = WriteBatchWithIndex::new(&Options::default(), 0, false);
+        assert_eq!(batch.iterator_with_base(db.raw_iterator()).err().unwrap().kind(),
+                   ErrorKind::InvalidArgument);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_write_batch_with_index_column_family() {
+    let path = "_rust_rocksdb_write_batch_with_index_cf_test";
+    {
+        let db = DB::open_default(path).unwrap();
+        let cf1 = db.create_cf("cf1", &Options::default()).unwrap();
+        db.put_cf(cf1, b"a", b"1").unwrap();
+        db.put(b"b", b"2").unwrap();
+
+        let mut batch = WriteBatchWithIndex::default();
+        batch.put_cf(cf1, b"c", b"3").unwrap();
+        batch.delete_cf(cf1, b"a").unwrap();
+        batch.put(b"d", b"4").unwrap();
+
+        assert_eq!(&*batch.get_from_batch_cf(cf1, b"c").unwrap().unwrap(), b"3");
+        assert!(batch.get_from_batch(b"c").unwrap().is_none());
+        assert!(batch.get_from_batch_and_db_cf(&db, cf1, b"a").unwrap().is_none());
+        assert_eq!(&*batch.get_from_batch_and_db(&db, b"b").unwrap().unwrap(), b"2");
+
+        {
+            let mut iter = batch.iterator_with_base_cf(db.raw_iterator_cf(cf1).unwrap(), cf1)
+                .unwrap();
+            assert_eq!(collect(&mut iter), vec![entry(b"c", b"3")]);
+            let mut iter = batch.iterator_with_base(db.raw_iterator()).unwrap();
+            assert_eq!(collect(&mut iter), vec![entry(b"b", b"2"), entry(b"d", b"4")]);
+        }
+
+        db.write(batch).unwrap();
+        assert!(db.get_cf(cf1, b"a").unwrap().is_none());
+        assert_eq!(&*db.get_cf(cf1, b"c").unwrap().unwrap(), b"3");
+        assert_eq!(&*db.get(b"d").unwrap().unwrap(), b"4");
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_write_batch_with_index_comparator() {
+    let path = "_rust_rocksdb_write_batch_with_index_comparator_test";
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_comparator("reverse", reverse_compare);
+    {
+        let db = DB::open(&opts, path).unwrap();
+        db.put(b"a", b"1").unwrap();
+        db.put(b"c", b"3").unwrap();
+
+        // A batch ordered byte-wise can't be read together with the database
+        let mut batch = WriteBatchWithIndex::default();
+        batch.put(b"b", b"2").unwrap();
+        assert_eq!(batch.get_from_batch_and_db(&db, b"a").err().unwrap().kind(),
+                   ErrorKind::InvalidArgument);
+        assert_eq!(batch.iterator_with_base(db.raw_iterator()).err().unwrap().kind(),
+                   ErrorKind::InvalidArgument);
+
+        let mut batch = WriteBatchWithIndex::new(&opts, 0, true);
+        batch.put(b"b", b"2").unwrap();
+        assert_eq!(&*batch.get_from_batch_and_db(&db, b"a").unwrap().unwrap(), b"1");
+        let mut iter = batch.iterator_with_base(db.raw_iterator()).unwrap();
+        assert_eq!(collect(&mut iter),
+                   vec![entry(b"c", b"3"), entry(b"b", b"2"), entry(b"a", b"1")]);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}
+
+#[test]
+pub fn test_write_batch_with_index_rtree_keys() {
+    let path = "_rust_rocksdb_write_batch_with_index_rtree_keys_test";
+    {
+        let db = DB::open_default(path).unwrap();
+        let rtree = db.create_rtree_cf("rtree", 2).unwrap();
+
+        let mut batch = WriteBatchWithIndex::default();
+        let key = RtreeKey::new("keypath", 1, vec![10.75, 11.11, 48.24, 48.50]);
+        batch.put_cf(rtree, &key.to_bytes(), b"augsburg").unwrap();
+        let key = RtreeKey::new("keypath", 2, vec![10.75, 11.11]);
+        assert!(batch.put_cf(rtree, &key.to_bytes(), b"augsburg").is_err());
+        assert!(batch.merge_cf(rtree, &key.to_bytes(), b"augsburg").is_err());
+        assert!(batch.delete_cf(rtree, b"not an R-tree key").is_err());
+        assert_eq!(batch.len(), 1);
+    }
+    assert!(DB::destroy(&Options::default(), path).is_ok());
+}